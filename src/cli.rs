@@ -0,0 +1,406 @@
+use crate::data::config::appearance::Config;
+use crate::data::config::project::ProjectConfig;
+use crate::file_manager::export::errors::ExportError;
+use crate::file_manager::export::html::export_html;
+use crate::file_manager::export::parse_page_ranges;
+use crate::file_manager::export::pdf::{export_pdf_document, PdfExportOptions};
+use crate::file_manager::export::png::{export_png_document, PngExportOptions};
+use crate::file_manager::export::svg::{export_svg_document, SvgExportOptions};
+use crate::file_manager::export::ExportType;
+use crate::file_manager::file::get_config_path;
+use crate::world::package::PackageStorage;
+use crate::world::TideWorld;
+use iced::futures::executor::block_on;
+use std::path::PathBuf;
+use typst::diag::{Severity, SourceDiagnostic, Warned};
+use typst::layout::PagedDocument;
+
+/// Usage of the command-line mode, printed by `tide help` and after invalid arguments.
+pub const USAGE: &str = "\
+Usage:
+  tide                            Launches the editor
+  tide compile [OPTIONS] [ROOT]   Compiles a project and prints its diagnostics
+  tide export [OPTIONS] [ROOT]    Compiles and exports a project
+  tide help                       Prints this help
+
+ROOT is the directory of the project, the current directory by default.
+Arguments that don't start with a command are left to the editor.
+The fonts, packages and project settings (tide.toml) are the ones used by the editor.
+
+Options:
+  -m, --main <FILE>       Main file, relative to ROOT [default: main.typ]
+  -f, --format <FORMAT>   Export format: pdf, svg, png or html [default: pdf]
+  -o, --output <PATH>     Exported file [default: ROOT/<main file name>.<format>]
+      --pages <RANGES>    Pages to export (e.g. 1-3,5) [default: the PDF pages of
+                          the project for PDF exports, every page otherwise]";
+
+/// Exit code of a failed compilation or export.
+pub const EXIT_FAILURE: i32 = 1;
+/// Exit code of invalid command-line arguments.
+pub const EXIT_USAGE: i32 = 2;
+
+/// A command run without launching the editor.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    /// Compiles the project and reports its diagnostics.
+    Compile(CliOptions),
+    /// Compiles the project and exports it.
+    Export(CliOptions),
+    /// Prints the usage of the command-line mode.
+    Help,
+}
+
+/// Options of the [`Command::Compile`] and [`Command::Export`] commands.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CliOptions {
+    /// Directory of the project.
+    pub root: PathBuf,
+    /// Main file, relative to the project root.
+    pub main: PathBuf,
+    /// Format of the export.
+    pub format: ExportType,
+    /// Path of the exported file, derived from the main file if unset.
+    pub output: Option<PathBuf>,
+    /// Page ranges to export, the project defaults if unset.
+    pub pages: Option<String>,
+}
+
+impl Default for CliOptions {
+    fn default() -> Self {
+        Self {
+            root: PathBuf::from("."),
+            main: PathBuf::from("main.typ"),
+            format: ExportType::PDF,
+            output: None,
+            pages: None,
+        }
+    }
+}
+
+/// Parses the command-line arguments, without the name of the executable.
+///
+/// Returns `None` if the first argument isn't a command, or if there is none,
+/// meaning that the editor must be launched with the arguments.
+///
+/// # Errors
+///
+/// Returns an error message if the command or one of its options is invalid.
+pub fn parse_args(
+    args: impl IntoIterator<Item = String>
+) -> Result<Option<Command>, String> {
+    let mut args = args.into_iter();
+    let Some(command) = args.next() else {
+        return Ok(None);
+    };
+    let compile = match command.as_str() {
+        "compile" => true,
+        "export" => false,
+        "help" | "-h" | "--help" => return Ok(Some(Command::Help)),
+        _ => return Ok(None),
+    };
+
+    let mut options = CliOptions::default();
+    let mut root = None;
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| format!("missing value of the {arg} option"))
+        };
+        match arg.as_str() {
+            "-m" | "--main" => options.main = PathBuf::from(value()?),
+            "-f" | "--format" => {
+                options.format = match value()?.to_lowercase().as_str() {
+                    "pdf" => ExportType::PDF,
+                    "svg" => ExportType::SVG,
+                    "png" => ExportType::PNG,
+                    "html" => ExportType::HTML,
+                    format => return Err(format!("unknown export format {format:?}")),
+                }
+            }
+            "-o" | "--output" => options.output = Some(PathBuf::from(value()?)),
+            "--pages" => options.pages = Some(value()?),
+            "-h" | "--help" => return Ok(Some(Command::Help)),
+            option if option.starts_with('-') => {
+                return Err(format!("unknown option {option:?}"))
+            }
+            _ if root.is_some() => return Err(format!("unexpected argument {arg:?}")),
+            _ => root = Some(PathBuf::from(arg)),
+        }
+    }
+    if let Some(root) = root {
+        options.root = root;
+    }
+
+    Ok(Some(if compile {
+        Command::Compile(options)
+    } else {
+        Command::Export(options)
+    }))
+}
+
+/// Runs a command and returns the exit code of the process.
+///
+/// Diagnostics are printed to `stderr`, see [`format_diagnostic`].
+pub fn run(command: Command) -> i32 {
+    let (options, export) = match command {
+        Command::Compile(options) => (options, false),
+        Command::Export(options) => (options, true),
+        Command::Help => {
+            println!("{USAGE}");
+            return 0;
+        }
+    };
+    let (world, project) = match project_world(&options) {
+        Ok(world) => world,
+        Err(err) => {
+            eprintln!("error: {err}");
+            return EXIT_FAILURE;
+        }
+    };
+
+    //the HTML target is compiled by its export
+    if export && options.format == ExportType::HTML {
+        let output = export_output(&world, &options);
+        let result = block_on(export_html(world.clone(), output)).map(|(path, warnings)| {
+            print_diagnostics(&world, &warnings);
+            print_diagnostics(&world, world.config_warnings());
+            path
+        });
+        return report_export(&world, result);
+    }
+
+    let Warned { output, mut warnings } = typst::compile::<PagedDocument>(&world);
+    warnings.extend(world.config_warnings().iter().cloned());
+    let document = match output {
+        Ok(document) => {
+            print_diagnostics(&world, &warnings);
+            document
+        }
+        Err(errors) => {
+            print_diagnostics(&world, &errors);
+            print_diagnostics(&world, &warnings);
+            eprintln!("error: compilation failed with {} error(s)", errors.len());
+            return EXIT_FAILURE;
+        }
+    };
+    if !export {
+        eprintln!("compiled {} page(s)", document.pages.len());
+        return 0;
+    }
+    let result = export_project(&world, &document, &project, &options);
+    report_export(&world, result)
+}
+
+/// Prints the result of an export and returns the exit code of the process.
+fn report_export(
+    world: &TideWorld,
+    result: Result<PathBuf, ExportError>,
+) -> i32 {
+    match result {
+        Ok(path) => {
+            eprintln!("exported {}", path.display());
+            0
+        }
+        Err(err) => {
+            match err {
+                ExportError::CompilationError(diagnostics)
+                | ExportError::PdfConformanceError(diagnostics) => {
+                    print_diagnostics(world, &diagnostics);
+                    eprintln!("error: export failed with {} error(s)", diagnostics.len());
+                }
+                err => eprintln!("error: {err}"),
+            }
+            EXIT_FAILURE
+        }
+    }
+}
+
+/// Creates the [`TideWorld`] of the project described by `options`,
+/// configured like in the editor, and returns it with the project configuration.
+///
+/// # Errors
+///
+/// Returns an error message if the project or its main file doesn't exist.
+fn project_world(options: &CliOptions) -> Result<(TideWorld, ProjectConfig), String> {
+    let root = options
+        .root
+        .canonicalize()
+        .map_err(|err| format!("project {}: {err}", options.root.display()))?;
+    let main = root.join(&options.main);
+    if !main.is_file() {
+        return Err(format!("main file {} not found", main.display()));
+    }
+    let main_id = TideWorld::id_from_path(&main, &root)
+        .ok_or_else(|| format!("main file {} is outside the project", main.display()))?;
+
+    let config = Config::load(get_config_path());
+    let packages = PackageStorage::new(config.typst.package_path, None);
+    let project = ProjectConfig::load(&root);
+    let mut world = TideWorld::new(root, main_id, None, packages);
+    world.configure(&project);
+    Ok((world, project))
+}
+
+/// Returns the path of the export described by `options`, without extension.
+fn export_output(
+    world: &TideWorld,
+    options: &CliOptions,
+) -> PathBuf {
+    options.output.clone().unwrap_or_else(|| {
+        let name = options.main.file_stem().unwrap_or_default();
+        world.root().join(name)
+    })
+}
+
+/// Exports the compiled `document` of the project in the paged format of `options`,
+/// and returns the path of the export.
+///
+/// # Errors
+///
+/// Returns a [`ExportError::InvalidOptions`] if the page ranges are invalid
+/// or the format isn't paged, or the error of the export.
+fn export_project(
+    world: &TideWorld,
+    document: &PagedDocument,
+    project: &ProjectConfig,
+    options: &CliOptions,
+) -> Result<PathBuf, ExportError> {
+    let output = export_output(world, options);
+    let pages = match (&options.pages, &options.format) {
+        (Some(pages), _) => Some(pages.as_str()),
+        (None, ExportType::PDF) => project.pdf.pages.as_deref(),
+        (None, _) => None,
+    };
+    let pages = parse_page_ranges(pages.unwrap_or_default())
+        .map_err(ExportError::InvalidOptions)?;
+
+    match options.format {
+        ExportType::PDF => {
+            let pdf_options = PdfExportOptions::from_project(&project.pdf, world, pages);
+            export_pdf_document(document, output, &pdf_options)
+        }
+        ExportType::SVG => {
+            let svg_options = SvgExportOptions {
+                pages,
+                ..SvgExportOptions::default()
+            };
+            export_svg_document(document, output, &svg_options)
+        }
+        ExportType::PNG => {
+            let png_options = PngExportOptions {
+                pages,
+                ..PngExportOptions::default()
+            };
+            export_png_document(document, output, &png_options)
+        }
+        ExportType::HTML => Err(ExportError::InvalidOptions(String::from(
+            "HTML isn't a paged format",
+        ))),
+        ExportType::Template => Err(ExportError::InvalidOptions(String::from(
+            "templates can't be exported from the command line",
+        ))),
+    }
+}
+
+/// Prints each diagnostic to `stderr`, see [`format_diagnostic`].
+fn print_diagnostics(
+    world: &TideWorld,
+    diagnostics: &[SourceDiagnostic],
+) {
+    for diagnostic in diagnostics {
+        eprintln!("{}", format_diagnostic(world, diagnostic));
+    }
+}
+
+/// Formats a diagnostic like a compiler: `file:line:column: severity: message`,
+/// followed by one line per hint.
+///
+/// The location is omitted if the diagnostic has none, see [`TideWorld::location`].
+pub fn format_diagnostic(
+    world: &TideWorld,
+    diagnostic: &SourceDiagnostic,
+) -> String {
+    let severity = match diagnostic.severity {
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+    };
+    let mut formatted = match world.location(diagnostic.span) {
+        Some(location) => format!("{location}: {severity}: {}", diagnostic.message),
+        None => format!("{severity}: {}", diagnostic.message),
+    };
+    for hint in &diagnostic.hints {
+        formatted.push_str(&format!("\n  = hint: {hint}"));
+    }
+    formatted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn args(args: &str) -> Result<Option<Command>, String> {
+        parse_args(args.split_whitespace().map(String::from))
+    }
+
+    #[test]
+    fn test_parse_args() {
+        assert_eq!(args(""), Ok(None));
+        assert_eq!(args("help"), Ok(Some(Command::Help)));
+        assert_eq!(
+            args("compile"),
+            Ok(Some(Command::Compile(CliOptions::default())))
+        );
+        assert_eq!(
+            args(
+                "export thesis -m src/thesis.typ --format SVG -o out/thesis --pages 1-2"
+            ),
+            Ok(Some(Command::Export(CliOptions {
+                root: PathBuf::from("thesis"),
+                main: PathBuf::from("src/thesis.typ"),
+                format: ExportType::SVG,
+                output: Some(PathBuf::from("out/thesis")),
+                pages: Some(String::from("1-2")),
+            })))
+        );
+        assert_eq!(args("thesis.typ"), Ok(None));
+        assert_eq!(args("build --main thesis.typ"), Ok(None));
+        assert!(args("export --format docx").is_err());
+        assert!(args("export --main").is_err());
+        assert!(args("compile a b").is_err());
+        assert!(args("compile --watch").is_err());
+    }
+
+    #[test]
+    fn test_run() {
+        let root = std::env::temp_dir().join("tide-test-cli");
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).unwrap();
+        fs::write(root.join("main.typ"), "= Title\n#include \"chapter.typ\"").unwrap();
+        fs::write(root.join("chapter.typ"), "Some text.").unwrap();
+
+        let options = CliOptions {
+            root: root.clone(),
+            ..CliOptions::default()
+        };
+        assert_eq!(run(Command::Compile(options.clone())), 0);
+        assert_eq!(run(Command::Export(options.clone())), 0);
+        assert!(root.join("main.pdf").is_file());
+
+        fs::write(root.join("chapter.typ"), "\n  #unknown").unwrap();
+        assert_eq!(run(Command::Export(options.clone())), EXIT_FAILURE);
+        let (world, _) = project_world(&options).unwrap();
+        let errors = typst::compile::<PagedDocument>(&world).output.unwrap_err();
+        assert_eq!(
+            format_diagnostic(&world, &errors[0]),
+            "chapter.typ:2:4: error: unknown variable: unknown"
+        );
+
+        let missing = CliOptions {
+            main: PathBuf::from("missing.typ"),
+            ..options
+        };
+        assert_eq!(run(Command::Compile(missing)), EXIT_FAILURE);
+        let _ = fs::remove_dir_all(root);
+    }
+}
@@ -1,2 +1,4 @@
 pub mod appearance;
+pub mod preset;
+pub mod project;
 pub mod serialization;
@@ -8,6 +8,8 @@ use std::{collections::HashMap, fs};
 /// These pairs are automatically inserted when typing in the editor.
 const DEFAULT_AUTO_PAIRS: [(char, char); 4] =
     [('(', ')'), ('"', '"'), ('[', ']'), ('{', '}')];
+/// Default delay after the last keystroke before the live preview compiles, in ms.
+const DEFAULT_LIVE_PREVIEW_DELAY: u64 = 500;
 
 /// Root configuration structure loaded from a TOML file.
 ///
@@ -20,6 +22,9 @@ pub struct Config {
     pub colors: ColorsConfig,
     /// Editor-specific configuration such as font size and auto-pairs.
     pub editor: EditorConfig,
+    /// Typst environment configuration such as the package directory.
+    #[serde(default)]
+    pub typst: TypstConfig,
 }
 
 /// Configuration for general application behavior.
@@ -42,6 +47,21 @@ pub struct EditorConfig {
     #[serde(default = "default_pairs")]
     pub auto_pairs: HashMap<char, char>,
     pub colors: HighlighterTheme,
+    /// Recompiles the preview automatically when typing stops, not only on save.
+    pub live_preview: bool,
+    /// Delay without typing after which the live preview is compiled, in milliseconds.
+    pub live_preview_delay: u64,
+}
+
+/// Configuration of the Typst environment used to compile documents.
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "kebab-case", default)]
+pub struct TypstConfig {
+    /// Additional directory searched for `@preview` and `@local` packages,
+    /// before the standard Typst data and cache directories.
+    pub package_path: Option<PathBuf>,
+    /// Reloads the fonts automatically when the user or project `fonts` directory changes.
+    pub watch_fonts: bool,
 }
 
 /// Configuration for UI theme colors.
@@ -187,6 +207,8 @@ impl Default for EditorConfig {
             font_size: 14,
             auto_pairs: HashMap::from(DEFAULT_AUTO_PAIRS),
             colors: HighlighterTheme::default(),
+            live_preview: false,
+            live_preview_delay: DEFAULT_LIVE_PREVIEW_DELAY,
         }
     }
 }
@@ -0,0 +1,184 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use typst_pdf::PdfStandard;
+
+/// A named way of exporting the project, stored in the `[[presets]]` of its `tide.toml`.
+///
+/// ```toml
+/// [[presets]]
+/// name = "website"
+/// format = "png"
+/// output = "out/{main}.png"
+/// pages = "1"
+/// ppi = 96.0
+/// background = "transparent"
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub struct ExportPreset {
+    /// Name of the preset, shown when reporting its result.
+    pub name: String,
+    /// Template of the output path, relative to the project root. See [`Self::output_path`].
+    pub output: String,
+    /// Page ranges to export (e.g. `1-3,5`), every page if unset. Ignored by HTML exports.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pages: Option<String>,
+    /// Format of the export and its specific options.
+    #[serde(flatten)]
+    pub format: PresetFormat,
+}
+
+/// Format of an [`ExportPreset`] and its specific options.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "format", rename_all = "kebab-case")]
+pub enum PresetFormat {
+    /// A PDF, see [`PdfExportOptions`](crate::file_manager::export::pdf::PdfExportOptions).
+    Pdf {
+        /// Standards the PDF must conform to (e.g. `["a-2b"]`).
+        #[serde(default)]
+        standards: Vec<PdfStandard>,
+        /// Whether to write a tagged PDF.
+        #[serde(default = "default_tagged")]
+        tagged: bool,
+    },
+    /// PNG images, see [`PngExportOptions`](crate::file_manager::export::png::PngExportOptions).
+    Png {
+        /// Resolution in pixels per inch.
+        #[serde(default = "default_ppi")]
+        ppi: f32,
+        /// `document`, `transparent` or a hexadecimal color (e.g. `#ffffff`).
+        #[serde(default = "default_background")]
+        background: String,
+    },
+    /// SVG files, see [`SvgExportOptions`](crate::file_manager::export::svg::SvgExportOptions).
+    Svg {
+        /// Whether to export all the pages in a single SVG file.
+        #[serde(default)]
+        merged: bool,
+        /// Gap between the pages of a merged SVG, in points.
+        #[serde(default = "default_gap")]
+        gap: f64,
+        /// Naming pattern of the files of a per-page export.
+        #[serde(default = "default_pattern")]
+        pattern: String,
+    },
+    /// A single HTML file.
+    Html,
+}
+
+/// PDFs are tagged unless disabled.
+fn default_tagged() -> bool {
+    true
+}
+
+/// See [`DEFAULT_PPI`](crate::file_manager::export::png::DEFAULT_PPI).
+fn default_ppi() -> f32 {
+    crate::file_manager::export::png::DEFAULT_PPI
+}
+
+/// PNG images use the fill of the pages unless specified.
+fn default_background() -> String {
+    String::from("document")
+}
+
+/// See [`DEFAULT_SVG_GAP`](crate::file_manager::export::svg::DEFAULT_SVG_GAP).
+fn default_gap() -> f64 {
+    crate::file_manager::export::svg::DEFAULT_SVG_GAP
+}
+
+/// See [`DEFAULT_SVG_PATTERN`](crate::file_manager::export::svg::DEFAULT_SVG_PATTERN).
+fn default_pattern() -> String {
+    String::from(crate::file_manager::export::svg::DEFAULT_SVG_PATTERN)
+}
+
+impl PresetFormat {
+    /// Returns the extension of the files of the format.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Pdf { .. } => "pdf",
+            Self::Png { .. } => "png",
+            Self::Svg { .. } => "svg",
+            Self::Html => "html",
+        }
+    }
+}
+
+impl ExportPreset {
+    /// Returns the output path of the preset, relative to the project `root`.
+    ///
+    /// The template can contain the following placeholders:
+    /// - `{project}`: the name of the project directory ;
+    /// - `{main}`: the name of the main file, without extension ;
+    /// - `{date}`: the date of the export, formatted as `YYYY-MM-DD`.
+    pub fn output_path(
+        &self,
+        root: &Path,
+        main: &str,
+        date: &str,
+    ) -> PathBuf {
+        let project = root
+            .file_name()
+            .map(|name| name.to_string_lossy())
+            .unwrap_or_default();
+        let output = self
+            .output
+            .replace("{project}", &project)
+            .replace("{main}", main)
+            .replace("{date}", date);
+        root.join(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Presets {
+        presets: Vec<ExportPreset>,
+    }
+
+    #[test]
+    fn test_deserialize_presets() {
+        let presets: Presets = toml::from_str(
+            r#"
+            [[presets]]
+            name = "archive"
+            format = "pdf"
+            output = "out/{main}-{date}.pdf"
+            standards = ["a-2b"]
+
+            [[presets]]
+            name = "website"
+            format = "png"
+            output = "out/{main}.png"
+            pages = "1"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            presets.presets[0].format,
+            PresetFormat::Pdf {
+                standards: vec![PdfStandard::A_2b],
+                tagged: true
+            }
+        );
+        assert_eq!(
+            presets.presets[1].format,
+            PresetFormat::Png {
+                ppi: default_ppi(),
+                background: default_background()
+            }
+        );
+        assert_eq!(presets.presets[1].pages.as_deref(), Some("1"));
+        let round_trip: Presets =
+            toml::from_str(&toml::to_string(&presets).unwrap()).unwrap();
+        assert_eq!(round_trip, presets);
+
+        let root = PathBuf::from("/projects/thesis");
+        assert_eq!(
+            presets.presets[0].output_path(&root, "main", "2024-05-01"),
+            root.join("out/main-2024-05-01.pdf")
+        );
+    }
+}
@@ -0,0 +1,435 @@
+use super::preset::ExportPreset;
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::Path;
+use toml_edit::{DocumentMut, Value};
+use typst::text::Lang;
+use typst_pdf::PdfStandard;
+
+/// Name of the configuration file found at the root of a project.
+pub const PROJECT_CONFIG_FILE_NAME: &str = "tide.toml";
+/// Environment variable fixing the build time, following the reproducible builds convention.
+const SOURCE_DATE_EPOCH: &str = "SOURCE_DATE_EPOCH";
+/// Format of the fixed date of a project.
+pub const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// Project-level configuration, stored in the `tide.toml` file at the root of the project.
+///
+/// Unlike the application [`Config`](super::appearance::Config), this configuration
+/// travels with the project and affects how its documents are compiled.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "kebab-case", default)]
+pub struct ProjectConfig {
+    /// Values exposed to the documents through `sys.inputs`.
+    pub inputs: BTreeMap<String, String>,
+    /// Default styles applied to every document of the project.
+    pub style: StyleConfig,
+    /// Settings making the builds of the project reproducible.
+    pub build: BuildConfig,
+    /// Default options of the PDF exports.
+    pub pdf: PdfConfig,
+    /// Named exports run together by "Export all".
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub presets: Vec<ExportPreset>,
+    /// Display settings of the preview of the project.
+    pub preview: PreviewConfig,
+}
+
+/// Default styles of a project, applied before the rules of the documents.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "kebab-case", default)]
+pub struct StyleConfig {
+    /// Base font family of the text.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub font: Option<String>,
+    /// Base size of the text, in points.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size: Option<f64>,
+    /// Language of the text, as an ISO 639-1/2/3 code (e.g. `en`, `fr`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lang: Option<String>,
+}
+
+/// Display settings of the preview of a project, kept from one session to the next.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "kebab-case", default)]
+pub struct PreviewConfig {
+    /// Zoom of the preview.
+    pub zoom: Zoom,
+    /// Whether the preview is displayed in inverted colors, for dark environments.
+    pub inverted: bool,
+}
+
+/// Zoom of the preview.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum Zoom {
+    /// The pages fill the width of the preview.
+    #[default]
+    FitWidth,
+    /// Each page fits entirely in the preview.
+    FitPage,
+    /// The pages are displayed at the given percentage of their actual size.
+    Percent(u16),
+}
+
+/// Reproducibility settings of a project.
+///
+/// When a build time is fixed, it is used as the date of the documents (`datetime.today()`)
+/// and as the creation date of the exported PDFs, so that exporting unchanged sources
+/// always gives the same files.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "kebab-case", default)]
+pub struct BuildConfig {
+    /// Fixed date of the documents, formatted as `YYYY-MM-DD`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub date: Option<String>,
+    /// Fixed build time, as a number of seconds since the Unix epoch.
+    ///
+    /// Ignored if `date` is set. If neither is set, the `SOURCE_DATE_EPOCH`
+    /// environment variable is used when defined.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_date_epoch: Option<i64>,
+}
+
+/// Default options of the PDF exports of a project.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "kebab-case", default)]
+pub struct PdfConfig {
+    /// Standards the PDF must conform to (e.g. `["a-2b"]`, `["ua-1"]`).
+    pub standards: Vec<PdfStandard>,
+    /// Whether to write a tagged PDF, for accessibility.
+    pub tagged: bool,
+    /// String stably identifying the document, instead of the project and main file paths.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ident: Option<String>,
+    /// Page ranges to export (e.g. `1-3,5`), every page if unset.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pages: Option<String>,
+    /// Whether to export the PDF again after every successful save.
+    pub export_on_save: bool,
+    /// Destination of the continuous exports, relative to the project root if inside it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
+}
+
+impl Default for PdfConfig {
+    fn default() -> Self {
+        Self {
+            standards: vec![],
+            tagged: true,
+            ident: None,
+            pages: None,
+            export_on_save: false,
+            output: None,
+        }
+    }
+}
+
+impl BuildConfig {
+    /// Returns the fixed build time of the project, or `None` to use the wall clock.
+    ///
+    /// An invalid `date` is ignored, see [`ProjectConfig::warnings`].
+    pub fn timestamp(&self) -> Option<DateTime<Utc>> {
+        let date = self
+            .date
+            .as_ref()
+            .and_then(|date| NaiveDate::parse_from_str(date, DATE_FORMAT).ok());
+        if let Some(date) = date {
+            return Some(date.and_time(Default::default()).and_utc());
+        }
+        let epoch = self.source_date_epoch.or_else(|| {
+            env::var(SOURCE_DATE_EPOCH)
+                .ok()
+                .and_then(|epoch| epoch.trim().parse().ok())
+        })?;
+        DateTime::from_timestamp(epoch, 0)
+    }
+}
+
+impl ProjectConfig {
+    /// Returns a message for each invalid setting of the project,
+    /// which the compilations ignore.
+    pub fn warnings(&self) -> Vec<String> {
+        let mut warnings = vec![];
+        if let Some(lang) = &self.style.lang {
+            if let Err(err) = lang.parse::<Lang>() {
+                warnings.push(format!("invalid project language {lang:?}: {err}"));
+            }
+        }
+        if let Some(date) = &self.build.date {
+            if let Err(err) = NaiveDate::parse_from_str(date, DATE_FORMAT) {
+                warnings.push(format!("invalid project date {date:?}: {err}"));
+            }
+        }
+        warnings
+    }
+
+    /// Loads the configuration of the project at `root`.
+    ///
+    /// If the file is missing or malformed, returns the default configuration.
+    pub fn load(root: &Path) -> Self {
+        let path = root.join(PROJECT_CONFIG_FILE_NAME);
+        if !path.is_file() {
+            return Self::default();
+        }
+        match fs::read_to_string(&path).map(|content| toml::from_str(&content)) {
+            Ok(Ok(config)) => config,
+            Ok(Err(e)) => {
+                eprintln!("Project configuration deserialization failed: {e}");
+                Self::default()
+            }
+            Err(e) => {
+                eprintln!("Error while loading the project configuration: {e}");
+                Self::default()
+            }
+        }
+    }
+
+    /// Writes the configuration in the `tide.toml` file of the project at `root`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`io::Error`] if the configuration can't be serialized or written.
+    pub fn save(
+        &self,
+        root: &Path,
+    ) -> io::Result<()> {
+        let content = toml::to_string_pretty(self).map_err(io::Error::other)?;
+        fs::write(root.join(PROJECT_CONFIG_FILE_NAME), content)
+    }
+
+    /// Writes the display settings of the preview in the `[preview]` table
+    /// of the `tide.toml` file of the project at `root`, see [`update_table`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`io::Error`] if the file can't be updated.
+    pub fn save_preview(
+        &self,
+        root: &Path,
+    ) -> io::Result<()> {
+        let preview = toml::to_string(&self.preview).map_err(io::Error::other)?;
+        let preview: DocumentMut = preview.parse().map_err(io::Error::other)?;
+        //nested tables (e.g. a percentage zoom) are written inline
+        let keys = preview
+            .iter()
+            .map(|(key, item)| (key, item.clone().into_value().ok()))
+            .collect();
+        update_table(root, "preview", keys)
+    }
+
+    /// Writes whether the PDF is exported on save, and its destination, in the `[pdf]`
+    /// table of the `tide.toml` file of the project at `root`, see [`update_table`].
+    ///
+    /// Unlike the settings of the interface, this is written in a new `tide.toml`
+    /// if the project has none.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`io::Error`] if the file can't be written.
+    pub fn save_export_on_save(
+        &self,
+        root: &Path,
+    ) -> io::Result<()> {
+        if !root.join(PROJECT_CONFIG_FILE_NAME).is_file() {
+            return self.save(root);
+        }
+        let keys = vec![
+            ("export-on-save", Some(Value::from(self.pdf.export_on_save))),
+            ("output", self.pdf.output.as_deref().map(Value::from)),
+        ];
+        update_table(root, "pdf", keys)
+    }
+}
+
+/// Sets the given keys of a table of the `tide.toml` file of the project at `root`,
+/// or removes those set to `None`.
+///
+/// The file is updated in place: its comments (including those of the replaced values),
+/// the order of its keys and the keys unknown to Tide are kept. Nothing is written
+/// if the project has no `tide.toml`, so that settings of the interface don't create one.
+///
+/// # Errors
+///
+/// Returns an [`io::Error`] if the file can't be read, parsed or written.
+fn update_table(
+    root: &Path,
+    table: &str,
+    keys: Vec<(&str, Option<Value>)>,
+) -> io::Result<()> {
+    let path = root.join(PROJECT_CONFIG_FILE_NAME);
+    if !path.is_file() {
+        return Ok(());
+    }
+    let mut document: DocumentMut = fs::read_to_string(&path)?
+        .parse()
+        .map_err(io::Error::other)?;
+    let entries = document
+        .entry(table)
+        .or_insert_with(toml_edit::table)
+        .as_table_like_mut()
+        .ok_or_else(|| io::Error::other(format!("`{table}` is not a table")))?;
+    for (key, value) in keys {
+        match value {
+            Some(mut value) => match entries.get_mut(key) {
+                //replacing the item keeps the comments above the key
+                Some(item) => {
+                    if let Some(old) = item.as_value() {
+                        *value.decor_mut() = old.decor().clone();
+                    }
+                    *item = value.into();
+                }
+                None => {
+                    entries.insert(key, value.into());
+                }
+            },
+            None => {
+                entries.remove(key);
+            }
+        }
+    }
+    fs::write(path, document.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::config::preset::PresetFormat;
+
+    #[test]
+    fn test_save_and_load() {
+        let root = std::env::temp_dir().join("tide-test-project-config");
+        fs::create_dir_all(&root).unwrap();
+        let _ = fs::remove_file(root.join(PROJECT_CONFIG_FILE_NAME));
+        assert_eq!(ProjectConfig::load(&root), ProjectConfig::default());
+
+        let config = ProjectConfig {
+            inputs: BTreeMap::from([("variant".to_string(), "draft".to_string())]),
+            style: StyleConfig {
+                font: Some("Libertinus Serif".to_string()),
+                size: Some(12.0),
+                lang: None,
+            },
+            build: BuildConfig {
+                date: Some("2024-05-01".to_string()),
+                source_date_epoch: None,
+            },
+            pdf: PdfConfig {
+                standards: vec![PdfStandard::A_2b],
+                tagged: false,
+                ident: None,
+                pages: Some("1-2".to_string()),
+                export_on_save: true,
+                output: Some("out/main.pdf".to_string()),
+            },
+            presets: vec![ExportPreset {
+                name: "website".to_string(),
+                output: "out/{main}.html".to_string(),
+                pages: None,
+                format: PresetFormat::Html,
+            }],
+            preview: PreviewConfig {
+                zoom: Zoom::Percent(150),
+                inverted: true,
+            },
+        };
+        config.save(&root).unwrap();
+        assert_eq!(ProjectConfig::load(&root), config);
+
+        fs::write(
+            root.join(PROJECT_CONFIG_FILE_NAME),
+            "[style]\nsize = \"big\"",
+        )
+        .unwrap();
+        assert_eq!(ProjectConfig::load(&root), ProjectConfig::default());
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn test_save_preview() {
+        let root = std::env::temp_dir().join("tide-test-save-preview");
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).unwrap();
+        let mut config = ProjectConfig::default();
+        config.preview.zoom = Zoom::Percent(150);
+        config.save_preview(&root).unwrap();
+        assert!(!root.join(PROJECT_CONFIG_FILE_NAME).exists());
+
+        let content = "# shared with the team\n[inputs]\nvariant = \"draft\"\n\n\
+                       [preview]\nzoom = \"fit-page\" # readable\nunknown = 1\n";
+        fs::write(root.join(PROJECT_CONFIG_FILE_NAME), content).unwrap();
+        config.save_preview(&root).unwrap();
+        let saved = fs::read_to_string(root.join(PROJECT_CONFIG_FILE_NAME)).unwrap();
+        assert!(saved.starts_with("# shared with the team\n[inputs]\n"));
+        assert!(saved.contains("zoom = { percent = 150 } # readable\nunknown = 1\n"));
+        let loaded = ProjectConfig::load(&root);
+        assert_eq!(loaded.preview, config.preview);
+        assert_eq!(loaded.inputs["variant"], "draft");
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn test_save_export_on_save() {
+        let root = std::env::temp_dir().join("tide-test-save-export-on-save");
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).unwrap();
+        let mut config = ProjectConfig::default();
+        config.pdf.export_on_save = true;
+        config.pdf.output = Some("out/main.pdf".to_string());
+        config.save_export_on_save(&root).unwrap();
+        assert_eq!(ProjectConfig::load(&root), config);
+
+        let content = "[pdf]\n# for the reviewers\nexport-on-save = true\n\
+                       output = \"out/main.pdf\"\n";
+        fs::write(root.join(PROJECT_CONFIG_FILE_NAME), content).unwrap();
+        config.pdf.export_on_save = false;
+        config.pdf.output = None;
+        config.save_export_on_save(&root).unwrap();
+        let saved = fs::read_to_string(root.join(PROJECT_CONFIG_FILE_NAME)).unwrap();
+        assert_eq!(saved, "[pdf]\n# for the reviewers\nexport-on-save = false\n");
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn test_build_timestamp() {
+        let build = BuildConfig {
+            date: Some("2024-05-01".to_string()),
+            source_date_epoch: Some(0),
+        };
+        let expected = DateTime::from_timestamp(1714521600, 0);
+        assert_eq!(build.timestamp(), expected);
+
+        let build = BuildConfig {
+            date: None,
+            source_date_epoch: Some(1714521600),
+        };
+        assert_eq!(build.timestamp(), expected);
+
+        //an invalid date falls back to the epoch
+        let build = BuildConfig {
+            date: Some("01/05/2024".to_string()),
+            source_date_epoch: Some(0),
+        };
+        assert_eq!(build.timestamp(), DateTime::from_timestamp(0, 0));
+    }
+
+    #[test]
+    fn test_warnings() {
+        let mut config = ProjectConfig::default();
+        config.style.lang = Some("fr".to_string());
+        config.build.date = Some("2024-05-01".to_string());
+        assert!(config.warnings().is_empty());
+
+        config.style.lang = Some("french".to_string());
+        config.build.date = Some("01/05/2024".to_string());
+        let warnings = config.warnings();
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings[0].starts_with("invalid project language \"french\""));
+        assert!(warnings[1].starts_with("invalid project date \"01/05/2024\""));
+    }
+}
@@ -4,4 +4,5 @@ pub mod editor;
 pub mod file_tree;
 pub mod modal;
 pub mod pop_up;
+pub mod preview;
 pub mod tooltip;
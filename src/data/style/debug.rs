@@ -1,4 +1,4 @@
-use iced::widget::container;
+use iced::widget::{container, text};
 use iced::{Border, Theme};
 
 /// Returns the style for debug containers.
@@ -18,3 +18,20 @@ pub fn debug_container_style(theme: &Theme) -> container::Style {
         ..Default::default()
     }
 }
+
+/// Returns the style of the text of an error in a debug container.
+pub fn error_text_style(theme: &Theme) -> text::Style {
+    let palette = theme.extended_palette();
+    text::Style {
+        color: Some(palette.danger.base.color),
+    }
+}
+
+/// Returns the style of the text of a warning in a debug container,
+/// distinct from the style of the errors.
+pub fn warning_text_style(theme: &Theme) -> text::Style {
+    let palette = theme.extended_palette();
+    text::Style {
+        color: Some(palette.warning.base.color),
+    }
+}
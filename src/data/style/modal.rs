@@ -1,5 +1,5 @@
 use iced::{
-    border,
+    border, Color,
     widget::{container::Style as ContainerStyle, text::Style as TextStyle, Theme},
 };
 
@@ -15,6 +15,17 @@ pub fn modal_style(theme: &Theme) -> ContainerStyle {
     }
 }
 
+/// Returns the style of the area displaying a font sample rendered by Typst.
+///
+/// The sample is drawn in black on a transparent page, so it gets a light background.
+pub fn font_sample_style(_theme: &Theme) -> ContainerStyle {
+    ContainerStyle {
+        background: Some(Color::WHITE.into()),
+        border: border::rounded(4),
+        ..Default::default()
+    }
+}
+
 /// Returns the text style used inside modal dialogs.
 ///
 /// Uses the danger base color to draw attention to critical actions.
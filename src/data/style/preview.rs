@@ -0,0 +1,32 @@
+use iced::widget::container::Style as ContainerStyle;
+use iced::widget::Theme;
+use iced::{border, Color};
+
+/// Returns the style of a page of the preview which is not rendered yet.
+///
+/// Pages are white unless the document sets another fill, so it is drawn as a blank page.
+pub fn page_placeholder_style(_theme: &Theme) -> ContainerStyle {
+    ContainerStyle {
+        background: Some(Color::WHITE.into()),
+        ..Default::default()
+    }
+}
+
+/// Returns the style of a page of the inverted preview which is not rendered yet.
+pub fn inverted_page_placeholder_style(_theme: &Theme) -> ContainerStyle {
+    ContainerStyle {
+        background: Some(Color::BLACK.into()),
+        ..Default::default()
+    }
+}
+
+/// Returns the style of the highlight briefly marking the position of the editor cursor
+/// in the preview.
+pub fn cursor_marker_style(theme: &Theme) -> ContainerStyle {
+    let palette = theme.extended_palette();
+    ContainerStyle {
+        background: Some(palette.primary.base.color.scale_alpha(0.3).into()),
+        border: border::rounded(2),
+        ..Default::default()
+    }
+}
@@ -117,11 +117,25 @@ mod tests {
         let content = Content::with_text(content);
         world.reload_source_from_content(main, &content);
         let source = world.source(main).unwrap();
-        let cursor = source.len_bytes() - 1; //EOF
+        let cursor = source.text().len(); //EOF, the content has no trailing newline
 
         (source, cursor - shift)
     }
 
+    /// Keeps the completions whose label starts with the given `word`.
+    fn complete_word(
+        word: &str,
+        completions: &[Completion],
+    ) -> Completions {
+        Some(
+            completions
+                .iter()
+                .filter(|c| c.label.starts_with(word))
+                .cloned()
+                .collect(),
+        )
+    }
+
     struct TestWorld {
         world: TideWorld,
         shift: usize,
@@ -154,7 +168,7 @@ mod tests {
                 typst_ide::autocomplete(&self.world, None, &source, cursor, true)
                     .unwrap_or((0, vec![]));
 
-            let word = source.get(pos..cursor)?;
+            let word = source.text().get(pos..cursor)?;
             let result = if word.is_empty() || word.eq("") {
                 None
             } else {
@@ -230,13 +244,13 @@ mod tests {
             .test_completion("#sym.arrow.")
             .includes(["b", "bar", "bl", "br", "curve", "dashed"])
             .excludes(["expression"])
-            .expects_len(32);
+            .expects_len(35);
         world
             .left_shift(1)
             .test_completion("#figure()")
             .includes(["image", "caption"])
             .excludes(["list", "array"])
-            .expects_len(12);
+            .expects_len(13);
         world
             .test_completion("")
             .includes(["label", "expression", "linebreak"])
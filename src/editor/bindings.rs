@@ -1,4 +1,5 @@
 use crate::file_manager::export::ExportType;
+use crate::screen::component::preview::zoom_shortcut;
 use crate::screen::{component::toolbar, editing};
 use iced::{
     keyboard::{key, Key},
@@ -19,6 +20,7 @@ use iced::{
 /// - `Ctrl + O` --> Move to the end of the line and break the current line
 /// - `Ctrl + E` --> Export current project as a PDF
 /// - `Ctrl + Space` --> Open the autocomplete context
+/// - `Ctrl + =`, `Ctrl + -` and `Ctrl + 0` --> Zoom the preview in, out, or to fit its width
 pub fn bindings(key_press: KeyPress) -> Option<Binding<editing::Message>> {
     match key_press.key.as_ref() {
         Key::Named(key::Named::Tab) => {
@@ -64,6 +66,10 @@ pub fn bindings(key_press: KeyPress) -> Option<Binding<editing::Message>> {
         Key::Named(key::Named::Space) if key_press.modifiers.command() => {
             Some(Binding::Custom(editing::Message::Autocomplete))
         }
+        Key::Character("=" | "+" | "-" | "0") if key_press.modifiers.command() => {
+            zoom_shortcut(&key_press.key)
+                .map(|message| Binding::Custom(editing::Message::Preview(message)))
+        }
         _ => Binding::from_key_press(key_press),
     }
 }
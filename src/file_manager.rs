@@ -1,3 +1,5 @@
 pub mod export;
 pub mod file;
 pub mod import;
+pub mod template;
+pub mod watch;
@@ -1,32 +1,191 @@
 use crate::file_manager::export::errors::ExportError;
 use crate::world::TideWorld;
+use std::fs;
+use std::num::NonZeroUsize;
+use std::path::Path;
 use typst::compile;
-use typst::diag::Warned;
-use typst::layout::PagedDocument;
+use typst::diag::{SourceDiagnostic, Warned};
+use typst::ecow::EcoVec;
+use typst::layout::{PageRanges, PagedDocument};
 
 pub mod errors;
+pub mod html;
 pub mod pdf;
+pub mod png;
+pub mod preset;
+pub mod preview;
 pub mod svg;
 pub mod template;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
+#[allow(clippy::upper_case_acronyms)]
 pub enum ExportType {
     PDF,
     SVG,
+    PNG,
+    HTML,
     Template,
 }
 
 /// Compiles the main Typst source in the given [`TideWorld`] into a [`PagedDocument`].
 ///
-/// Captures any warnings during compilation and logs them to `stderr`.
+/// Returns the document with the warnings of the compilation.
 ///
 /// # Errors
 ///
-/// Returns a [`ExportError::CompilationError`] if the Typst compilation fails.
-fn compile_document(world: &TideWorld) -> Result<PagedDocument, ExportError> {
+/// Returns a [`ExportError::CompilationError`] if the Typst compilation fails,
+/// see [`compilation_error`].
+fn compile_document(
+    world: &TideWorld,
+) -> Result<Warned<PagedDocument>, ExportError> {
     let Warned { output, warnings } = compile(world);
-    if !warnings.is_empty() {
-        eprintln!("Typst warnings: {:?}", warnings);
+    match output {
+        Ok(document) => Ok(Warned {
+            output: document,
+            warnings,
+        }),
+        Err(errors) => Err(compilation_error(errors, warnings)),
+    }
+}
+
+/// Returns the [`ExportError::CompilationError`] of a failed compilation,
+/// holding its errors followed by its warnings.
+fn compilation_error(
+    mut errors: EcoVec<SourceDiagnostic>,
+    warnings: EcoVec<SourceDiagnostic>,
+) -> ExportError {
+    errors.extend(warnings);
+    ExportError::CompilationError(errors)
+}
+
+/// Writes `content` to `path` atomically.
+///
+/// The content is first written to a hidden temporary file in the same directory,
+/// then renamed over `path`, so that viewers watching the file never read it half-written.
+///
+/// # Errors
+///
+/// Returns a [`ExportError::FileWriteError`] if writing or renaming fails.
+fn write_atomic(
+    path: &Path,
+    content: impl AsRef<[u8]>,
+) -> Result<(), ExportError> {
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    let temp_path = path.with_file_name(format!(".{file_name}.tide-tmp"));
+    fs::write(&temp_path, content)
+        .and_then(|_| fs::rename(&temp_path, path))
+        .map_err(|err| {
+            let _ = fs::remove_file(&temp_path);
+            ExportError::FileWriteError(err)
+        })
+}
+
+/// Parses a comma-separated list of one-indexed page ranges, such as `1-3,5,8-`.
+///
+/// A range can be open on either side (`-3`, `8-`). Returns `None` if `text` is blank,
+/// meaning that every page is selected.
+///
+/// # Errors
+///
+/// Returns a warning text if a range is malformed or empty.
+pub fn parse_page_ranges(text: &str) -> Result<Option<PageRanges>, String> {
+    if text.trim().is_empty() {
+        return Ok(None);
+    }
+    let page = |page: &str| -> Result<Option<NonZeroUsize>, String> {
+        let page = page.trim();
+        if page.is_empty() {
+            return Ok(None);
+        }
+        page.parse()
+            .map(Some)
+            .map_err(|_| format!("Invalid page number: {page:?}"))
+    };
+    let mut ranges = vec![];
+    for range in text.split(',') {
+        let (start, end) = match range.split_once('-') {
+            Some((start, end)) => (page(start)?, page(end)?),
+            None => {
+                let page = page(range)?.ok_or("Empty page range")?;
+                (Some(page), Some(page))
+            }
+        };
+        if let (Some(start), Some(end)) = (start, end) {
+            if start > end {
+                return Err(format!("Invalid page range: {}", range.trim()));
+            }
+        }
+        ranges.push(start..=end);
+    }
+    Ok(Some(PageRanges::new(ranges)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::tests::init_world;
+    use typst::diag::Severity;
+    use typst::syntax::Source;
+    use typst::World;
+
+    #[test]
+    fn test_parse_page_ranges() {
+        assert!(parse_page_ranges(" ").unwrap().is_none());
+
+        let ranges = parse_page_ranges("1-2, 4,6-").unwrap().unwrap();
+        let included: Vec<_> = (0..8)
+            .filter(|&index| ranges.includes_page_index(index))
+            .collect();
+        assert_eq!(included, [0, 1, 3, 5, 6, 7]);
+
+        let ranges = parse_page_ranges("-2").unwrap().unwrap();
+        assert!(ranges.includes_page_index(0) && !ranges.includes_page_index(2));
+
+        assert!(parse_page_ranges("0").is_err());
+        assert!(parse_page_ranges("3-1").is_err());
+        assert!(parse_page_ranges("1,,2").is_err());
+        assert!(parse_page_ranges("a-b").is_err());
+    }
+
+    #[test]
+    fn test_compile_document() {
+        let mut world = init_world();
+        let main = world.main();
+        let source = |text: &str| Source::new(main, String::from(text));
+
+        world.add_source(main, source("#text(font: \"Unknown Family\")[Text]"));
+        let Warned { output, warnings } = compile_document(&world).unwrap();
+        assert_eq!(output.pages.len(), 1);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].severity, Severity::Warning);
+
+        world.add_source(main, source("#text(font: \"Unknown Family\")[Text] #unknown"));
+        let Err(ExportError::CompilationError(diagnostics)) = compile_document(&world)
+        else {
+            panic!("the compilation should fail");
+        };
+        let severities: Vec<_> = diagnostics.iter().map(|d| d.severity).collect();
+        assert_eq!(severities, [Severity::Error, Severity::Warning]);
+    }
+
+    #[test]
+    fn test_write_atomic() {
+        let dir = std::env::temp_dir().join("tide-test-write-atomic");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("out.pdf");
+
+        write_atomic(&path, "first").unwrap();
+        write_atomic(&path, "second").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "second");
+        //only the written file is left
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+
+        let missing = dir.join("missing").join("out.pdf");
+        assert!(matches!(
+            write_atomic(&missing, "content"),
+            Err(ExportError::FileWriteError(_))
+        ));
+        let _ = fs::remove_dir_all(dir);
     }
-    output.map_err(ExportError::CompilationError)
 }
@@ -12,12 +12,26 @@ use typst::ecow::EcoVec;
 pub enum ExportError {
     /// The Typst compilation process failed.
     ///
-    /// Contains a message detailing the compilation error.
+    /// Contains the errors of the compilation, followed by its warnings.
     CompilationError(EcoVec<SourceDiagnostic>),
     /// PDF generation failed after successful compilation.
     ///
     /// Contains a message describing the reason for the failure.
     PdfGenerationError(String),
+    /// The document doesn't conform to the PDF standards required for the export.
+    ///
+    /// Contains the diagnostics of the violations.
+    PdfConformanceError(EcoVec<SourceDiagnostic>),
+    /// PNG encoding of a rendered page failed.
+    ///
+    /// Contains a message describing the reason for the failure.
+    PngEncodingError(String),
+    /// The selected page ranges don't contain any page of the document.
+    NoPageSelected,
+    /// The options of the export are invalid.
+    ///
+    /// Contains a message describing the invalid option.
+    InvalidOptions(String),
     /// Writing a file to disk failed.
     ///
     /// Wraps a standard I/O error from the filesystem.
@@ -40,6 +54,23 @@ impl Display for ExportError {
             ExportError::PdfGenerationError(msg) => {
                 write!(f, "Can't generate PDF: {}", msg)
             }
+            ExportError::PdfConformanceError(diagnostics) => {
+                write!(f, "The document doesn't conform to the required PDF standards:")?;
+                for diagnostic in diagnostics {
+                    write!(f, "\n- {}", diagnostic.message)?;
+                    for hint in &diagnostic.hints {
+                        write!(f, "\n  hint: {}", hint)?;
+                    }
+                }
+                Ok(())
+            }
+            ExportError::PngEncodingError(msg) => {
+                write!(f, "Can't encode PNG: {}", msg)
+            }
+            ExportError::NoPageSelected => {
+                write!(f, "The selected page ranges don't contain any page.")
+            }
+            ExportError::InvalidOptions(msg) => write!(f, "Invalid export options: {}", msg),
             ExportError::FileWriteError(err) => write!(f, "Can't write file: {}", err),
             ExportError::FileReadError(err) => write!(f, "Can't read file: {}", err),
         }
@@ -64,6 +95,14 @@ impl Clone for ExportError {
             ExportError::PdfGenerationError(msg) => {
                 ExportError::PdfGenerationError(msg.clone())
             }
+            ExportError::PdfConformanceError(diagnostics) => {
+                ExportError::PdfConformanceError(diagnostics.clone())
+            }
+            ExportError::PngEncodingError(msg) => {
+                ExportError::PngEncodingError(msg.clone())
+            }
+            ExportError::NoPageSelected => ExportError::NoPageSelected,
+            ExportError::InvalidOptions(msg) => ExportError::InvalidOptions(msg.clone()),
             ExportError::FileWriteError(err) => {
                 ExportError::FileWriteError(io::Error::new(err.kind(), err.to_string()))
             }
@@ -0,0 +1,170 @@
+use crate::file_manager::export::errors::ExportError;
+use crate::file_manager::export::{compilation_error, write_atomic};
+use crate::world::TideWorld;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use typst::compile;
+use typst::diag::{SourceDiagnostic, Warned};
+use typst::ecow::EcoVec;
+use typst::World;
+use typst_html::{html, HtmlAttr, HtmlDocument, HtmlElement, HtmlNode};
+
+/// Attributes of HTML elements which may reference a local file.
+const ASSET_ATTRS: [HtmlAttr; 2] =
+    [HtmlAttr::constant("src"), HtmlAttr::constant("href")];
+
+/// Exports the main Typst source, compiled for the HTML target with the experimental
+/// HTML feature enabled, as a single HTML file at the specified `output_path`.
+///
+/// Appends the `.html` extension automatically. Local files referenced by the document
+/// (e.g. `html.img(src: "figure.png")`) are copied next to the HTML file,
+/// at the same relative path. Images placed with `image` are embedded in the HTML file.
+///
+/// Returns the path of the HTML file and the warnings of the compilation,
+/// which notably report the elements not supported by the HTML export yet.
+///
+/// # Errors
+///
+/// Returns a [`ExportError`] if compilation, HTML generation, or file writing fails.
+pub async fn export_html(
+    world: TideWorld,
+    output_path: PathBuf,
+) -> Result<(PathBuf, EcoVec<SourceDiagnostic>), ExportError> {
+    let world = world.for_html();
+    let Warned { output, warnings } = compile::<HtmlDocument>(&world);
+    let document = output.map_err(|errors| compilation_error(errors, warnings.clone()))?;
+    let html_content =
+        html(&document).map_err(|errors| compilation_error(errors, warnings.clone()))?;
+    let output_path = output_path.with_extension("html");
+    write_atomic(&output_path, html_content)?;
+
+    let mut assets = vec![];
+    collect_assets(&document.root, &mut assets);
+    let source_dir = world
+        .main()
+        .vpath()
+        .resolve(world.root())
+        .and_then(|main| main.parent().map(Path::to_path_buf))
+        .unwrap_or_else(|| world.root().to_path_buf());
+    let output_dir = output_path.parent().unwrap_or(Path::new("."));
+    copy_assets(&source_dir, output_dir, &assets)?;
+
+    Ok((output_path, warnings))
+}
+
+/// Collects the local paths referenced by `element` and its descendants.
+fn collect_assets(
+    element: &HtmlElement,
+    assets: &mut Vec<PathBuf>,
+) {
+    for attr in ASSET_ATTRS {
+        if let Some(path) = element.attrs.get(attr).and_then(|value| local_path(value)) {
+            if !assets.contains(&path) {
+                assets.push(path);
+            }
+        }
+    }
+    for child in &element.children {
+        if let HtmlNode::Element(child) = child {
+            collect_assets(child, assets);
+        }
+    }
+}
+
+/// Returns the relative path referenced by an attribute value, if it references a local file.
+///
+/// URLs with a scheme (`https:`, `data:`, `mailto:`...), absolute paths, fragments
+/// and paths leaving the directory of the document are ignored.
+fn local_path(reference: &str) -> Option<PathBuf> {
+    let path = reference.split(['?', '#']).next()?;
+    if path.is_empty() || path.contains(':') || path.starts_with('/') {
+        return None;
+    }
+    let path = PathBuf::from(path);
+    path.components()
+        .all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+        .then_some(path)
+}
+
+/// Copies the existing files among `assets` from `source_dir` to `output_dir`,
+/// keeping their relative paths.
+///
+/// # Errors
+///
+/// Returns a [`ExportError::FileWriteError`] if a file can't be copied.
+fn copy_assets(
+    source_dir: &Path,
+    output_dir: &Path,
+    assets: &[PathBuf],
+) -> Result<(), ExportError> {
+    for asset in assets {
+        let source = source_dir.join(asset);
+        let destination = output_dir.join(asset);
+        if !source.is_file() || source == destination {
+            continue;
+        }
+        if let Some(parent) = destination.parent() {
+            fs::create_dir_all(parent).map_err(ExportError::FileWriteError)?;
+        }
+        fs::copy(&source, &destination).map_err(ExportError::FileWriteError)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::package::PackageStorage;
+    use typst::syntax::{FileId, VirtualPath};
+
+    #[test]
+    fn test_local_path() {
+        assert_eq!(
+            local_path("figures/plot.png?v=2"),
+            Some(PathBuf::from("figures/plot.png"))
+        );
+        assert_eq!(local_path("style.css"), Some(PathBuf::from("style.css")));
+        assert_eq!(local_path("https://typst.app"), None);
+        assert_eq!(local_path("data:image/png;base64,AAAA"), None);
+        assert_eq!(local_path("#intro"), None);
+        assert_eq!(local_path("/etc/passwd"), None);
+        assert_eq!(local_path("../secret.txt"), None);
+    }
+
+    #[tokio::test]
+    async fn test_export_html() {
+        let root = std::env::temp_dir().join("tide-test-export-html");
+        let output = std::env::temp_dir().join("tide-test-export-html-output");
+        let _ = fs::remove_dir_all(&root);
+        let _ = fs::remove_dir_all(&output);
+        fs::create_dir_all(root.join("figures")).unwrap();
+        fs::create_dir_all(&output).unwrap();
+        fs::write(
+            root.join("main.typ"),
+            "= Notes\n\
+             #html.img(src: \"figures/plot.svg\", alt: \"plot\")\n\
+             #link(\"https://typst.app\")[Typst]",
+        )
+        .unwrap();
+        fs::write(root.join("figures").join("plot.svg"), "<svg/>").unwrap();
+
+        let main_file_id = FileId::new(None, VirtualPath::new("main.typ"));
+        let world = TideWorld::new(
+            root.clone(),
+            main_file_id,
+            None,
+            PackageStorage::new(None, None),
+        );
+        let (path, warnings) = export_html(world, output.join("notes")).await.unwrap();
+        assert_eq!(path, output.join("notes.html"));
+        assert!(fs::read_to_string(&path)
+            .unwrap()
+            .contains("<h2>Notes</h2>"));
+        assert!(output.join("figures").join("plot.svg").is_file());
+        //the HTML export is experimental
+        assert!(!warnings.is_empty());
+
+        let _ = fs::remove_dir_all(root);
+        let _ = fs::remove_dir_all(output);
+    }
+}
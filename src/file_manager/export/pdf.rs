@@ -1,13 +1,158 @@
-use crate::file_manager::export::compile_document;
+use crate::data::config::project::PdfConfig;
+use crate::file_manager::export::{compile_document, write_atomic};
 use crate::file_manager::export::errors::ExportError;
 use crate::world::TideWorld;
+use chrono::{DateTime, Datelike, Timelike, Utc};
 use std::path::PathBuf;
-use typst::layout::PagedDocument;
-use typst_pdf::{pdf, PdfOptions};
+use typst::foundations::{Datetime, Smart};
+use typst::layout::{PageRanges, PagedDocument};
+use typst::World;
+use typst_pdf::{pdf, PdfOptions, PdfStandard, PdfStandards, Timestamp};
 
-/// Exports the compiled document as a PDF at the specified `output_path`.
+/// PDF versions which can be enforced, with their names.
+pub const PDF_VERSIONS: [(PdfStandard, &str); 5] = [
+    (PdfStandard::V_1_4, "PDF 1.4"),
+    (PdfStandard::V_1_5, "PDF 1.5"),
+    (PdfStandard::V_1_6, "PDF 1.6"),
+    (PdfStandard::V_1_7, "PDF 1.7"),
+    (PdfStandard::V_2_0, "PDF 2.0"),
+];
+
+/// PDF substandards (archival and accessibility) which can be enforced, with their names.
+///
+/// Typst only supports one substandard at a time.
+pub const PDF_SUBSTANDARDS: [(PdfStandard, &str); 12] = [
+    (PdfStandard::A_1b, "PDF/A-1b"),
+    (PdfStandard::A_1a, "PDF/A-1a"),
+    (PdfStandard::A_2b, "PDF/A-2b"),
+    (PdfStandard::A_2u, "PDF/A-2u"),
+    (PdfStandard::A_2a, "PDF/A-2a"),
+    (PdfStandard::A_3b, "PDF/A-3b"),
+    (PdfStandard::A_3u, "PDF/A-3u"),
+    (PdfStandard::A_3a, "PDF/A-3a"),
+    (PdfStandard::A_4, "PDF/A-4"),
+    (PdfStandard::A_4f, "PDF/A-4f"),
+    (PdfStandard::A_4e, "PDF/A-4e"),
+    (PdfStandard::Ua_1, "PDF/UA-1"),
+];
+
+/// Options of a PDF export, owning the values borrowed by [`PdfOptions`].
+#[derive(Debug, Clone)]
+pub struct PdfExportOptions {
+    /// String that stably identifies the document, hashed into the PDF identifier.
+    ///
+    /// If `None`, a hash of the document's title and author is used instead.
+    pub ident: Option<String>,
+    /// Creation time of the document, used unless the document sets its own date.
+    pub timestamp: Option<DateTime<Utc>>,
+    /// Pages to export, `None` for every page.
+    pub pages: Option<PageRanges>,
+    /// Standards the PDF must conform to.
+    pub standards: Vec<PdfStandard>,
+    /// Whether to write a tagged PDF. Always the case with PDF/UA-1.
+    pub tagged: bool,
+}
+
+impl Default for PdfExportOptions {
+    fn default() -> Self {
+        Self {
+            ident: None,
+            timestamp: None,
+            pages: None,
+            standards: vec![],
+            tagged: true,
+        }
+    }
+}
+
+impl PdfExportOptions {
+    /// Returns the options of the PDF exports of a project, as configured in `tide.toml`,
+    /// restricted to the given `pages`.
+    ///
+    /// The identifier defaults to [`default_ident`], and the creation date is the fixed time
+    /// of a reproducible project, or the current time.
+    pub fn from_project(
+        config: &PdfConfig,
+        world: &TideWorld,
+        pages: Option<PageRanges>,
+    ) -> Self {
+        Self {
+            ident: Some(config.ident.clone().unwrap_or_else(|| default_ident(world))),
+            timestamp: Some(world.timestamp().unwrap_or_else(Utc::now)),
+            pages,
+            standards: config.standards.clone(),
+            tagged: config.tagged,
+        }
+    }
+
+    /// Returns the [`PdfOptions`] given to Typst.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ExportError::PdfGenerationError`] if the standards are incompatible.
+    fn to_pdf_options(&self) -> Result<PdfOptions<'_>, ExportError> {
+        Ok(PdfOptions {
+            ident: self.ident.as_deref().map_or(Smart::Auto, Smart::Custom),
+            timestamp: self.timestamp.and_then(to_timestamp),
+            page_ranges: self.pages.clone(),
+            standards: pdf_standards(&self.standards)
+                .map_err(ExportError::PdfGenerationError)?,
+            tagged: self.tagged,
+        })
+    }
+}
+
+/// Returns the identifier of the PDF exports of a project which doesn't define one.
+///
+/// Made of the names of the project directory and of the main file.
+pub fn default_ident(world: &TideWorld) -> String {
+    let project_name = world
+        .root()
+        .file_name()
+        .map(|name| name.to_string_lossy())
+        .unwrap_or_default();
+    format!(
+        "{}/{}",
+        project_name,
+        world.main().vpath().as_rootless_path().display()
+    )
+}
+
+/// Checks that the given standards can be enforced together.
+///
+/// # Errors
+///
+/// Returns a message explaining why the standards are incompatible.
+pub fn pdf_standards(standards: &[PdfStandard]) -> Result<PdfStandards, String> {
+    PdfStandards::new(standards)
+        .map_err(|err| format!("Invalid PDF standards: {err}"))
+}
+
+/// Returns the name of a PDF standard (e.g. "PDF/A-2b").
+pub fn pdf_standard_name(standard: PdfStandard) -> &'static str {
+    PDF_VERSIONS
+        .iter()
+        .chain(PDF_SUBSTANDARDS.iter())
+        .find(|(known, _)| *known == standard)
+        .map_or("unknown standard", |(_, name)| name)
+}
+
+/// Converts a UTC time into a PDF [`Timestamp`].
+fn to_timestamp(time: DateTime<Utc>) -> Option<Timestamp> {
+    let datetime = Datetime::from_ymd_hms(
+        time.year(),
+        time.month().try_into().ok()?,
+        time.day().try_into().ok()?,
+        time.hour().try_into().ok()?,
+        time.minute().try_into().ok()?,
+        time.second().try_into().ok()?,
+    )?;
+    Some(Timestamp::new_utc(datetime))
+}
+
+/// Compiles the main Typst source and exports it as a PDF at the specified `output_path`.
 ///
-/// Appends the `.pdf` extension automatically. Uses the provided [`PdfOptions`].
+/// See [`export_pdf_document`].
 ///
 /// # Errors
 ///
@@ -15,36 +160,159 @@ use typst_pdf::{pdf, PdfOptions};
 pub async fn export_pdf(
     world: TideWorld,
     output_path: PathBuf,
-    pdf_options: PdfOptions<'_>,
+    pdf_options: PdfExportOptions,
 ) -> Result<PathBuf, ExportError> {
-    let document = compile_document(&world)?;
-    let pdf_content = generate_pdf(&document, &pdf_options)?;
+    let document = compile_document(&world)?.output;
+    export_pdf_document(&document, output_path, &pdf_options)
+}
+
+/// Exports an already compiled document as a PDF at the specified `output_path`.
+///
+/// Appends the `.pdf` extension automatically. Uses the provided [`PdfExportOptions`].
+///
+/// # Errors
+///
+/// Returns a [`ExportError`] if PDF generation or file writing fails,
+/// or if no page is selected.
+pub fn export_pdf_document(
+    document: &PagedDocument,
+    output_path: PathBuf,
+    pdf_options: &PdfExportOptions,
+) -> Result<PathBuf, ExportError> {
+    if let Some(pages) = &pdf_options.pages {
+        if !(0..document.pages.len()).any(|i| pages.includes_page_index(i)) {
+            return Err(ExportError::NoPageSelected);
+        }
+    }
+    let pdf_content = generate_pdf(document, pdf_options)?;
     let output_path = output_path.with_extension("pdf");
-    write_pdf(output_path.to_str().unwrap(), &pdf_content)?;
-    Ok(PathBuf::from(output_path))
+    write_atomic(&output_path, pdf_content)?;
+    Ok(output_path)
 }
 
 /// Converts a compiled Typst [`PagedDocument`] into a PDF byte vector.
 ///
 /// # Errors
 ///
-/// Returns a [`ExportError::PdfGenerationError`] on failure.
+/// Returns a [`ExportError::PdfConformanceError`] if the document doesn't conform
+/// to the required standards, or a [`ExportError::PdfGenerationError`] on other failures.
 fn generate_pdf(
     document: &PagedDocument,
-    pdf_options: &PdfOptions,
+    pdf_options: &PdfExportOptions,
 ) -> Result<Vec<u8>, ExportError> {
-    pdf(document, pdf_options)
-        .map_err(|e| ExportError::PdfGenerationError(format!("{:?}", e)))
+    pdf(document, &pdf_options.to_pdf_options()?).map_err(|diagnostics| {
+        if pdf_options.standards.is_empty() {
+            ExportError::PdfGenerationError(
+                diagnostics
+                    .iter()
+                    .map(|diagnostic| diagnostic.message.as_str())
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            )
+        } else {
+            ExportError::PdfConformanceError(diagnostics)
+        }
+    })
 }
 
-/// Writes the PDF byte content to the specified file path.
-///
-/// # Errors
-///
-/// Returns a [`ExportError::FileWriteError`] if writing fails.
-fn write_pdf(
-    output_path: &str,
-    content: &[u8],
-) -> Result<(), ExportError> {
-    std::fs::write(output_path, content).map_err(|e| ExportError::FileWriteError(e))
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::config::project::ProjectConfig;
+    use crate::world::package::PackageStorage;
+    use crate::world::tests::init_world;
+    use std::fs;
+    use std::time::Duration;
+    use typst::syntax::{FileId, VirtualPath};
+
+    #[test]
+    fn test_reproducible_pdf() {
+        let world = init_world();
+        let options = PdfExportOptions {
+            ident: Some(String::from("tide/main")),
+            timestamp: DateTime::from_timestamp(1714521600, 0),
+            ..PdfExportOptions::default()
+        };
+        let document = compile_document(&world).unwrap().output;
+        let first = generate_pdf(&document, &options).unwrap();
+        let second = generate_pdf(&document, &options).unwrap();
+        assert_eq!(first, second);
+    }
+
+    #[tokio::test]
+    async fn test_project_build_date() {
+        let root = std::env::temp_dir().join("tide-test-project-build-date");
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).unwrap();
+        fs::write(
+            root.join("main.typ"),
+            "#assert.eq(datetime.today(), datetime(year: 2024, month: 5, day: 1))\n\
+             = Reproducible",
+        )
+        .unwrap();
+        let main_file_id = FileId::new(None, VirtualPath::new("main.typ"));
+        let mut world = TideWorld::new(
+            root.clone(),
+            main_file_id,
+            None,
+            PackageStorage::new(None, None),
+        );
+        let mut config = ProjectConfig::default();
+        config.build.date = Some(String::from("2024-05-01"));
+        world.configure(&config);
+
+        //the options are computed for each export, as on save
+        let export = |path: &str| {
+            let options = PdfExportOptions::from_project(&config.pdf, &world, None);
+            assert_eq!(options.timestamp, DateTime::from_timestamp(1714521600, 0));
+            export_pdf(world.clone(), root.join(path), options)
+        };
+        let first = export("first").await.unwrap();
+        std::thread::sleep(Duration::from_millis(1100));
+        let second = export("second").await.unwrap();
+        assert!(fs::read(first).unwrap() == fs::read(second).unwrap());
+
+        //the epoch of the project is used without a date
+        config.build.date = None;
+        config.build.source_date_epoch = Some(1714521600);
+        world.configure(&config);
+        let options = PdfExportOptions::from_project(&config.pdf, &world, None);
+        assert_eq!(options.timestamp, DateTime::from_timestamp(1714521600, 0));
+        assert!(compile_document(&world).is_ok());
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn test_pdf_standards() {
+        let world = init_world();
+        let document = compile_document(&world).unwrap().output;
+
+        //PDF/A requires a document date
+        let options = PdfExportOptions {
+            standards: vec![PdfStandard::A_2b],
+            ..PdfExportOptions::default()
+        };
+        let Err(ExportError::PdfConformanceError(diagnostics)) =
+            generate_pdf(&document, &options)
+        else {
+            panic!("the document should not conform to PDF/A-2b");
+        };
+        assert!(diagnostics[0].message.starts_with("PDF/A-2b error"));
+
+        let options = PdfExportOptions {
+            timestamp: DateTime::from_timestamp(1714521600, 0),
+            ..options
+        };
+        assert!(generate_pdf(&document, &options).is_ok());
+
+        let options = PdfExportOptions {
+            standards: vec![PdfStandard::A_2b, PdfStandard::Ua_1],
+            ..options
+        };
+        assert!(matches!(
+            generate_pdf(&document, &options),
+            Err(ExportError::PdfGenerationError(_))
+        ));
+        assert_eq!(pdf_standard_name(PdfStandard::A_2b), "PDF/A-2b");
+    }
 }
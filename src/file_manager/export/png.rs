@@ -0,0 +1,203 @@
+use crate::file_manager::export::{compile_document, write_atomic};
+use crate::file_manager::export::errors::ExportError;
+use crate::world::TideWorld;
+use std::path::{Path, PathBuf};
+use typst::foundations::Smart;
+use typst::layout::{Page, PageRanges, PagedDocument};
+use typst::visualize::Color;
+use typst_render::render;
+
+/// Default resolution of PNG exports, in pixels per inch.
+pub const DEFAULT_PPI: f32 = 144.0;
+/// Number of points per inch.
+const POINTS_PER_INCH: f32 = 72.0;
+
+/// Background of the exported images.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum PngBackground {
+    /// Uses the fill of the pages, white unless the document sets `page(fill: ...)`.
+    #[default]
+    Document,
+    /// Leaves the pages unfilled.
+    Transparent,
+    /// Fills every page with the given color.
+    Fill(Color),
+}
+
+/// Options of a PNG export.
+#[derive(Debug, Clone)]
+pub struct PngExportOptions {
+    /// Resolution of the images, in pixels per inch.
+    pub ppi: f32,
+    /// Pages to export, `None` for every page.
+    pub pages: Option<PageRanges>,
+    /// Background of the images.
+    pub background: PngBackground,
+}
+
+impl Default for PngExportOptions {
+    fn default() -> Self {
+        Self {
+            ppi: DEFAULT_PPI,
+            pages: None,
+            background: PngBackground::default(),
+        }
+    }
+}
+
+/// Compiles the main Typst source and exports its selected pages as individual PNG files,
+/// see [`export_png_document`].
+///
+/// # Errors
+///
+/// Returns a [`ExportError`] if compilation, encoding or writing fails,
+/// or if no page is selected.
+pub async fn export_png(
+    world: TideWorld,
+    output_path: PathBuf,
+    options: PngExportOptions,
+) -> Result<PathBuf, ExportError> {
+    let document = compile_document(&world)?.output;
+    export_png_document(&document, output_path, &options)
+}
+
+/// Exports the selected pages of an already compiled document as individual PNG files.
+///
+/// PNG files are named using the base of `output_path` with page numbers appended,
+/// and written in the directory of `output_path`. Returns the path of the first file.
+///
+/// # Errors
+///
+/// Returns a [`ExportError`] if encoding or writing fails, or if no page is selected.
+pub fn export_png_document(
+    document: &PagedDocument,
+    output_path: PathBuf,
+    options: &PngExportOptions,
+) -> Result<PathBuf, ExportError> {
+    let png_content = generate_png(document, options)?;
+    if png_content.is_empty() {
+        return Err(ExportError::NoPageSelected);
+    }
+    write_png(&output_path.with_extension(""), png_content)
+}
+
+/// Renders and encodes each selected page of a [`PagedDocument`] as PNG.
+///
+/// Returns the index of each page in the document with its PNG data.
+///
+/// # Errors
+///
+/// Returns a [`ExportError::PngEncodingError`] on failure.
+fn generate_png(
+    document: &PagedDocument,
+    options: &PngExportOptions,
+) -> Result<Vec<(usize, Vec<u8>)>, ExportError> {
+    let pixel_per_pt = options.ppi / POINTS_PER_INCH;
+    document
+        .pages
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| {
+            options
+                .pages
+                .as_ref()
+                .is_none_or(|pages| pages.includes_page_index(*i))
+        })
+        .map(|(i, page)| {
+            let page = with_background(page, &options.background);
+            render(&page, pixel_per_pt)
+                .encode_png()
+                .map(|png| (i, png))
+                .map_err(|e| ExportError::PngEncodingError(e.to_string()))
+        })
+        .collect()
+}
+
+/// Returns a copy of `page` filled with the given background.
+fn with_background(
+    page: &Page,
+    background: &PngBackground,
+) -> Page {
+    let mut page = page.clone();
+    match background {
+        PngBackground::Document => {}
+        PngBackground::Transparent => page.fill = Smart::Custom(None),
+        PngBackground::Fill(color) => page.fill = Smart::Custom(Some((*color).into())),
+    }
+    page
+}
+
+/// Writes each PNG image to disk as a separate file using the given base path,
+/// and returns the path of the first file.
+///
+/// Each file is named `<base>-<page_number>.png`, page numbers starting at 1.
+///
+/// # Errors
+///
+/// Returns a [`ExportError::FileWriteError`] on write failure,
+/// or a [`ExportError::NoPageSelected`] if there is no image.
+fn write_png(
+    output_base: &Path,
+    content: Vec<(usize, Vec<u8>)>,
+) -> Result<PathBuf, ExportError> {
+    let base_name = output_base
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy();
+    let mut first = None;
+    for (i, page) in content {
+        let number = i + 1;
+        let output_path = output_base.with_file_name(format!("{base_name}-{number}.png"));
+        write_atomic(&output_path, page)?;
+        first.get_or_insert(output_path);
+    }
+    first.ok_or(ExportError::NoPageSelected)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file_manager::export::parse_page_ranges;
+    use crate::world::tests::init_world;
+    use typst::syntax::Source;
+    use typst::World;
+
+    #[tokio::test]
+    async fn test_export_png() {
+        let mut world = init_world();
+        let main = world.main();
+        world.add_source(
+            main,
+            Source::new(
+                main,
+                String::from("#set page(width: 72pt, height: 36pt)\nA #pagebreak() B #pagebreak() C"),
+            ),
+        );
+        let dir = std::env::temp_dir().join("tide-test-export-png");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let options = PngExportOptions {
+            ppi: 144.0,
+            pages: parse_page_ranges("2-").unwrap(),
+            background: PngBackground::Transparent,
+        };
+        let first = export_png(world.clone(), dir.join("out.png"), options)
+            .await
+            .unwrap();
+        assert_eq!(first, dir.join("out-2.png"));
+        assert!(!dir.join("out-1.png").exists());
+        let png = std::fs::read(dir.join("out-2.png")).unwrap();
+        assert!(dir.join("out-3.png").exists());
+        //the IHDR chunk starts with the width and height of the image
+        assert_eq!(&png[16..24], &[0, 0, 0, 144, 0, 0, 0, 72]);
+
+        let options = PngExportOptions {
+            pages: parse_page_ranges("4-").unwrap(),
+            ..PngExportOptions::default()
+        };
+        let result = export_png(world, dir.join("out.png"), options).await;
+        assert!(matches!(result, Err(ExportError::NoPageSelected)));
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,143 @@
+use crate::data::config::preset::{ExportPreset, PresetFormat};
+use crate::file_manager::export::errors::ExportError;
+use crate::file_manager::export::html::export_html;
+use crate::file_manager::export::parse_page_ranges;
+use crate::file_manager::export::pdf::{export_pdf, PdfExportOptions};
+use crate::file_manager::export::png::{export_png, PngBackground, PngExportOptions};
+use crate::file_manager::export::svg::{export_svg, SvgExportOptions};
+use crate::world::TideWorld;
+use std::path::PathBuf;
+use typst::visualize::Color;
+
+/// Exports the project as described by `preset`, at `output_path`.
+///
+/// Missing parent directories of `output_path` are created. The identifier and timestamp
+/// of PDF exports are taken from `pdf_defaults`.
+///
+/// Returns the path of the exported file (or directory, for per-page exports).
+///
+/// # Errors
+///
+/// Returns a [`ExportError::InvalidOptions`] if the preset is invalid, or the error of the export.
+pub async fn export_preset(
+    world: TideWorld,
+    preset: ExportPreset,
+    output_path: PathBuf,
+    pdf_defaults: PdfExportOptions,
+) -> Result<PathBuf, ExportError> {
+    let pages = parse_page_ranges(preset.pages.as_deref().unwrap_or_default())
+        .map_err(ExportError::InvalidOptions)?;
+    if let Some(parent) = output_path.parent() {
+        std::fs::create_dir_all(parent).map_err(ExportError::FileWriteError)?;
+    }
+
+    match preset.format {
+        PresetFormat::Pdf { standards, tagged } => {
+            let options = PdfExportOptions {
+                pages,
+                standards,
+                tagged,
+                ..pdf_defaults
+            };
+            export_pdf(world, output_path, options).await
+        }
+        PresetFormat::Png { ppi, background } => {
+            if ppi <= 0.0 {
+                return Err(ExportError::InvalidOptions(String::from(
+                    "resolution must be a positive number",
+                )));
+            }
+            let options = PngExportOptions {
+                ppi,
+                pages,
+                background: parse_background(&background)?,
+            };
+            export_png(world, output_path, options).await
+        }
+        PresetFormat::Svg {
+            merged,
+            gap,
+            pattern,
+        } => {
+            let options = SvgExportOptions {
+                pattern,
+                pages,
+                merged,
+                gap,
+            };
+            export_svg(world, output_path, options).await
+        }
+        PresetFormat::Html => export_html(world, output_path)
+            .await
+            .map(|(path, _warnings)| path),
+    }
+}
+
+/// Parses the background of a PNG preset: `document`, `transparent` or a hexadecimal color.
+///
+/// # Errors
+///
+/// Returns a [`ExportError::InvalidOptions`] if the background is none of them.
+fn parse_background(background: &str) -> Result<PngBackground, ExportError> {
+    match background.trim() {
+        "document" => Ok(PngBackground::Document),
+        "transparent" => Ok(PngBackground::Transparent),
+        color => color
+            .parse::<Color>()
+            .map(PngBackground::Fill)
+            .map_err(|err| {
+                ExportError::InvalidOptions(format!("background {color:?}: {err}"))
+            }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::tests::init_world;
+
+    #[tokio::test]
+    async fn test_export_preset() {
+        let dir = std::env::temp_dir().join("tide-test-export-preset");
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let preset = ExportPreset {
+            name: String::from("archive"),
+            output: String::from("out/{main}.pdf"),
+            pages: None,
+            format: PresetFormat::Pdf {
+                standards: vec![],
+                tagged: true,
+            },
+        };
+        let output_path = preset.output_path(&dir, "main", "2024-05-01");
+        let result = export_preset(
+            init_world(),
+            preset,
+            output_path,
+            PdfExportOptions::default(),
+        )
+        .await;
+        assert_eq!(result.unwrap(), dir.join("out").join("main.pdf"));
+
+        let preset = ExportPreset {
+            name: String::from("website"),
+            output: String::from("out/{main}.png"),
+            pages: Some(String::from("1")),
+            format: PresetFormat::Png {
+                ppi: 72.0,
+                background: String::from("not a color"),
+            },
+        };
+        let output_path = preset.output_path(&dir, "main", "2024-05-01");
+        let result = export_preset(
+            init_world(),
+            preset,
+            output_path,
+            PdfExportOptions::default(),
+        )
+        .await;
+        assert!(matches!(result, Err(ExportError::InvalidOptions(_))));
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,200 @@
+use crate::file_manager::export::compile_document;
+use crate::file_manager::export::errors::ExportError;
+use crate::world::TideWorld;
+use typst::diag::Warned;
+use typst::foundations::Smart;
+use typst::layout::{Frame, FrameItem, GroupItem, Page, PagedDocument};
+use typst::text::TextItem;
+use typst::utils::hash128;
+use typst::visualize::{Color, FixedStroke, Oklab, Paint, Shape};
+use typst_render::render;
+
+/// A page rendered as a bitmap for the preview.
+#[derive(Debug, Clone)]
+pub struct RasterPage {
+    /// Width of the bitmap, in pixels.
+    pub width: u32,
+    /// Height of the bitmap, in pixels.
+    pub height: u32,
+    /// Pixels of the bitmap, row by row, as non-premultiplied RGBA.
+    pub pixels: Vec<u8>,
+}
+
+/// Compiles the document of `world` for the preview, on a blocking thread so that
+/// the other tasks keep running during the compilation.
+///
+/// Returns the document with the warnings of the compilation.
+///
+/// # Errors
+///
+/// Returns a [`ExportError::CompilationError`] if the document fails to compile.
+pub async fn compile_preview(
+    world: TideWorld
+) -> Result<Warned<PagedDocument>, ExportError> {
+    match tokio::task::spawn_blocking(move || compile_document(&world)).await {
+        Ok(result) => result,
+        Err(err) => std::panic::resume_unwind(err.into_panic()),
+    }
+}
+
+/// Returns a hash of the content of `page`.
+///
+/// Pages with the same hash render the same, so their bitmaps can be reused
+/// from one compilation to the next.
+pub fn page_hash(page: &Page) -> u128 {
+    hash128(page)
+}
+
+/// Renders `page` as a bitmap, with `pixel_per_pt` pixels per point, on a blocking thread
+/// so that the other tasks keep running during the rendering.
+///
+/// If `inverted`, the colors of the page are inverted first (see [`invert_page`]).
+pub async fn render_page(
+    page: Page,
+    pixel_per_pt: f32,
+    inverted: bool,
+) -> RasterPage {
+    let render = move || rasterize_page(page, pixel_per_pt, inverted);
+    match tokio::task::spawn_blocking(render).await {
+        Ok(raster) => raster,
+        Err(err) => std::panic::resume_unwind(err.into_panic()),
+    }
+}
+
+/// Renders `page` as a bitmap, see [`render_page`].
+fn rasterize_page(
+    page: Page,
+    pixel_per_pt: f32,
+    inverted: bool,
+) -> RasterPage {
+    let page = if inverted { invert_page(&page) } else { page };
+    let pixmap = render(&page, pixel_per_pt);
+    let pixels = pixmap
+        .pixels()
+        .iter()
+        .flat_map(|pixel| {
+            let color = pixel.demultiply();
+            [color.red(), color.green(), color.blue(), color.alpha()]
+        })
+        .collect();
+    RasterPage {
+        width: pixmap.width(),
+        height: pixmap.height(),
+        pixels,
+    }
+}
+
+/// Returns `page` with the lightness of its colors inverted, keeping their hue,
+/// for a dark preview.
+///
+/// The background, text and shapes are inverted, while images, gradients and tilings
+/// are left untouched.
+pub fn invert_page(page: &Page) -> Page {
+    let fill = page.fill_or_white().map(invert_paint);
+    Page {
+        frame: invert_frame(&page.frame),
+        fill: Smart::Custom(fill),
+        ..page.clone()
+    }
+}
+
+/// Returns `frame` with the lightness of the colors of its items inverted.
+fn invert_frame(frame: &Frame) -> Frame {
+    let mut inverted = Frame::new(frame.size(), frame.kind());
+    if frame.has_baseline() {
+        inverted.set_baseline(frame.baseline());
+    }
+    for (pos, item) in frame.items() {
+        let item = match item {
+            FrameItem::Group(group) => FrameItem::Group(GroupItem {
+                frame: invert_frame(&group.frame),
+                ..group.clone()
+            }),
+            FrameItem::Text(text) => FrameItem::Text(TextItem {
+                fill: invert_paint(text.fill.clone()),
+                stroke: text.stroke.clone().map(invert_stroke),
+                ..text.clone()
+            }),
+            FrameItem::Shape(shape, span) => FrameItem::Shape(
+                Shape {
+                    fill: shape.fill.clone().map(invert_paint),
+                    stroke: shape.stroke.clone().map(invert_stroke),
+                    ..shape.clone()
+                },
+                *span,
+            ),
+            item => item.clone(),
+        };
+        inverted.push(*pos, item);
+    }
+    inverted
+}
+
+/// Returns `stroke` with the lightness of its paint inverted.
+fn invert_stroke(stroke: FixedStroke) -> FixedStroke {
+    FixedStroke {
+        paint: invert_paint(stroke.paint),
+        ..stroke
+    }
+}
+
+/// Returns `paint` with its lightness inverted if it is a solid color.
+fn invert_paint(paint: Paint) -> Paint {
+    match paint {
+        Paint::Solid(color) => Paint::Solid(invert_color(color)),
+        paint => paint,
+    }
+}
+
+/// Returns `color` with its perceived lightness inverted, keeping its hue and alpha.
+fn invert_color(color: Color) -> Color {
+    let lab = color.to_oklab();
+    Color::Oklab(Oklab::new(1.0 - lab.l, lab.a, lab.b, lab.alpha))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::tests::init_world;
+    use typst::syntax::Source;
+    use typst::visualize::ColorSpace;
+    use typst::World;
+
+    #[tokio::test]
+    async fn test_render_page() {
+        let mut world = init_world();
+        let document = compile_preview(world.clone()).await.unwrap().output;
+        let page = document.pages[0].clone();
+        let again = compile_preview(world.clone()).await.unwrap().output;
+        assert_eq!(page_hash(&page), page_hash(&again.pages[0]));
+
+        let main = world.main();
+        world.add_source(main, Source::new(main, String::from("= Goodbye World")));
+        let changed = compile_preview(world).await.unwrap().output;
+        assert_ne!(page_hash(&page), page_hash(&changed.pages[0]));
+
+        let size = page.frame.size();
+        let inverted = render_page(page.clone(), 0.5, true).await;
+        assert_eq!(inverted.pixels[..4], [0, 0, 0, 255]);
+        let raster = render_page(page, 0.5, false).await;
+        assert_eq!(raster.width, (size.x.to_pt() * 0.5).ceil() as u32);
+        assert_eq!(raster.height, (size.y.to_pt() * 0.5).ceil() as u32);
+        assert_eq!(
+            raster.pixels.len(),
+            (raster.width * raster.height * 4) as usize
+        );
+        assert_eq!(raster.pixels[..4], [255, 255, 255, 255]);
+    }
+
+    #[test]
+    fn test_invert_color() {
+        let rgb = |color: Color| color.to_space(ColorSpace::Srgb).to_vec4_u8();
+        assert_eq!(rgb(invert_color(Color::BLACK)), [255, 255, 255, 255]);
+        assert_eq!(rgb(invert_color(Color::WHITE)), [0, 0, 0, 255]);
+
+        //a dark blue becomes a light blue
+        let [red, green, blue, alpha] = rgb(invert_color(Color::NAVY));
+        assert!(blue > red && blue > green && red > 128);
+        assert_eq!(alpha, 255);
+    }
+}
@@ -1,27 +1,144 @@
-use crate::file_manager::export::compile_document;
+use crate::file_manager::export::{compile_document, write_atomic};
 use crate::file_manager::export::errors::ExportError;
 use crate::world::TideWorld;
-use std::ffi::OsStr;
-use std::path::PathBuf;
-use typst::layout::PagedDocument;
-use typst_svg::svg;
+use std::path::{Path, PathBuf};
+use typst::diag::Warned;
+use typst::layout::{Abs, PageRanges, PagedDocument};
+use typst_svg::{svg, svg_merged};
 
-/// Exports each page of the compiled Typst document as an individual SVG file.
-///
-/// SVG files are named using the base of `output_path` with page indices appended.
+/// Default naming pattern of the files of a per-page SVG export.
+pub const DEFAULT_SVG_PATTERN: &str = "{name}-{i}";
+/// Default gap between the pages of a merged SVG export, in points.
+pub const DEFAULT_SVG_GAP: f64 = 10.0;
+
+/// Options of an SVG export.
+#[derive(Debug, Clone)]
+pub struct SvgExportOptions {
+    /// Naming pattern of the files of a per-page export. See [`page_file_name`].
+    pub pattern: String,
+    /// Pages to export, `None` for every page.
+    pub pages: Option<PageRanges>,
+    /// Whether to export all the pages in a single SVG file, one below the other.
+    pub merged: bool,
+    /// Gap around and between the pages of a merged export, in points.
+    pub gap: f64,
+}
+
+impl Default for SvgExportOptions {
+    fn default() -> Self {
+        Self {
+            pattern: String::from(DEFAULT_SVG_PATTERN),
+            pages: None,
+            merged: false,
+            gap: DEFAULT_SVG_GAP,
+        }
+    }
+}
+
+/// Compiles the main Typst source and exports its selected pages as SVG,
+/// see [`export_svg_document`].
 ///
 /// # Errors
 ///
-/// Returns a [`ExportError`] if compilation or writing fails.
+/// Returns a [`ExportError`] if compilation or writing fails, or if no page is selected.
 pub async fn export_svg(
     world: TideWorld,
     output_path: PathBuf,
+    options: SvgExportOptions,
+) -> Result<PathBuf, ExportError> {
+    let document = compile_document(&world)?.output;
+    export_svg_document(&document, output_path, &options)
+}
+
+/// Exports the selected pages of an already compiled document as SVG,
+/// in the directory of `output_path`.
+///
+/// By default, each page is written in an individual file, named after `options.pattern`.
+/// In merged mode, all the pages are written in a single `.svg` file at `output_path`.
+///
+/// Returns the path of the merged file, or of the directory of the pages.
+///
+/// # Errors
+///
+/// Returns a [`ExportError`] if writing fails, or if no page is selected.
+pub fn export_svg_document(
+    document: &PagedDocument,
+    output_path: PathBuf,
+    options: &SvgExportOptions,
 ) -> Result<PathBuf, ExportError> {
-    let document = compile_document(&world)?;
+    let total = document.pages.len();
+    let indices = page_indices(total, options.pages.as_ref());
+    if indices.is_empty() {
+        return Err(ExportError::NoPageSelected);
+    }
+    let document = PagedDocument {
+        pages: indices.iter().map(|&i| document.pages[i].clone()).collect(),
+        ..document.clone()
+    };
+
+    if options.merged {
+        let output_path = output_path.with_extension("svg");
+        let content = svg_merged(&document, Abs::pt(options.gap));
+        write_atomic(&output_path, content)?;
+        return Ok(output_path);
+    }
+
     let svg_content = generate_svg(&document);
-    let output_base_name = output_path.file_name().unwrap_or(OsStr::new("output"));
-    write_svg(output_base_name.to_str().unwrap(), svg_content)?;
-    Ok(PathBuf::from(output_base_name))
+    let output_dir = output_path.parent().unwrap_or(Path::new(".")).to_path_buf();
+    let base_name = output_path
+        .file_stem()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| String::from("output"));
+    write_svg(
+        &output_dir,
+        &options.pattern,
+        &base_name,
+        total,
+        &indices,
+        svg_content,
+    )?;
+    Ok(output_dir)
+}
+
+/// Returns the zero-based indices of the selected pages, among `total` pages.
+fn page_indices(
+    total: usize,
+    pages: Option<&PageRanges>,
+) -> Vec<usize> {
+    (0..total)
+        .filter(|&index| pages.is_none_or(|pages| pages.includes_page_index(index)))
+        .collect()
+}
+
+/// Returns the name of the file of a page, following the given pattern.
+///
+/// The pattern can contain the following placeholders:
+/// - `{name}`: the name of the file chosen in the save dialog, without extension ;
+/// - `{i}`: the zero-based index of the page ;
+/// - `{p}`: the one-based number of the page ;
+/// - `{0p}`: the number of the page, padded with zeros to the width of `{t}` ;
+/// - `{t}`: the total number of pages.
+///
+/// The `.svg` extension is appended if the pattern doesn't contain it.
+pub fn page_file_name(
+    pattern: &str,
+    base_name: &str,
+    index: usize,
+    total: usize,
+) -> String {
+    let number = index + 1;
+    let width = total.to_string().len();
+    let name = pattern
+        .replace("{name}", base_name)
+        .replace("{i}", &index.to_string())
+        .replace("{0p}", &format!("{number:0width$}"))
+        .replace("{p}", &number.to_string())
+        .replace("{t}", &total.to_string());
+    if name.ends_with(".svg") {
+        name
+    } else {
+        name + ".svg"
+    }
 }
 
 //EXPORT EACH PAGE/FRAME
@@ -30,58 +147,99 @@ fn generate_svg(document: &PagedDocument) -> Vec<String> {
     document.pages.iter().map(|page| svg(page)).collect()
 }
 
-/// Writes each SVG string to disk as a separate file using the given base name.
+/// Writes each SVG string to disk as a separate file in `output_dir`.
 ///
-/// Each file is named `<base>-<page_index>.svg`.
+/// Each file is named after `pattern`, using the index of its page among `indices`.
 ///
 /// # Errors
 ///
 /// Returns a [`ExportError::FileWriteError`] on write failure.
 fn write_svg(
-    output_base_name: &str,
+    output_dir: &Path,
+    pattern: &str,
+    base_name: &str,
+    total: usize,
+    indices: &[usize],
     content: Vec<String>,
 ) -> Result<(), ExportError> {
-    for (i, page) in content.iter().enumerate() {
+    for (&index, page) in indices.iter().zip(content.iter()) {
         let output_path =
-            output_base_name.to_owned() + "-" + i.to_string().as_str() + ".svg";
-        std::fs::write(output_path, page).map_err(|e| ExportError::FileWriteError(e))?
+            output_dir.join(page_file_name(pattern, base_name, index, total));
+        write_atomic(&output_path, page)?
     }
     Ok(())
 }
 
-/// Compiles and previews all document pages as in-memory SVG strings. This is used for project preview
-/// within the application.
+/// Compiles and previews all document pages as in-memory SVG strings. This is used for small
+/// renderings within the application, such as font samples (the document preview is rendered
+/// as bitmaps, see [`crate::file_manager::export::preview`]).
 ///
 /// This does not write any files.
 ///
+/// Returns the SVG of each page with the warnings of the compilation.
+///
 /// # Errors
 ///
 /// Returns a [`ExportError`] if the document fails to compile.
-pub async fn preview_svg(world: TideWorld) -> Result<Vec<String>, ExportError> {
-    let document = compile_document(&world)?;
-    Ok(generate_svg(&document))
+pub async fn preview_svg(world: TideWorld) -> Result<Warned<Vec<String>>, ExportError> {
+    let Warned { output, warnings } = compile_document(&world)?;
+    Ok(Warned {
+        output: generate_svg(&output),
+        warnings,
+    })
 }
 
-/*
-ASYNC SVG GENERATION
-... is not possible currently in Iced
-see: https://discord.com/channels/628993209984614400/1355550014990057533/1358593193599439039
-
-A possible workaround is using `resvg` to render to an image concurrently in a Task.
-But Typst can also render documents as Pixmap (RGBA pixel buffers).
-That means we could generate some Pixmap and load them asynchronously with (iced)::Handle::from_rgba() in a Task.
-Then, rendering a lower quality preview when the user resizes it.
-With some other optimizations (rendering only the pages that are visible on the screen, or reusing the handles),
-we could remove all the freezes and crashes that occurred with SVG.
-We would need `tiny-skia` and `typst-render`.
-
-fn test_generate_render(document: &PagedDocument) -> Vec<Pixmap> {
-    document.pages.iter().map(|page| render(page, 3.0)).collect()
-}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file_manager::export::parse_page_ranges;
+    use crate::world::tests::init_world;
+    use typst::syntax::Source;
+    use typst::World;
 
-pub async fn test_preview_raw(world: TideWorld) -> Result<Vec<Pixmap>, ExportError> {
-    let document = compile_document(&world)?;
-    Ok(test_generate_render(&document))
-}
+    #[test]
+    fn test_page_file_name() {
+        assert_eq!(
+            page_file_name(DEFAULT_SVG_PATTERN, "doc", 0, 3),
+            "doc-0.svg"
+        );
+        assert_eq!(
+            page_file_name("{name}_{0p}of{t}", "doc", 2, 12),
+            "doc_03of12.svg"
+        );
+        assert_eq!(page_file_name("page-{p}.svg", "doc", 4, 5), "page-5.svg");
+    }
 
- */
+    #[tokio::test]
+    async fn test_export_svg() {
+        let mut world = init_world();
+        let main = world.main();
+        world.add_source(
+            main,
+            Source::new(main, String::from("A #pagebreak() B #pagebreak() C")),
+        );
+        let dir = std::env::temp_dir().join("tide-test-export-svg");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let options = SvgExportOptions {
+            pattern: String::from("{name}-{p}"),
+            pages: parse_page_ranges("2-3").unwrap(),
+            ..SvgExportOptions::default()
+        };
+        let result = export_svg(world.clone(), dir.join("doc.svg"), options).await;
+        assert_eq!(result.unwrap(), dir);
+        assert!(!dir.join("doc-1.svg").exists());
+        assert!(dir.join("doc-2.svg").is_file() && dir.join("doc-3.svg").is_file());
+
+        let options = SvgExportOptions {
+            merged: true,
+            ..SvgExportOptions::default()
+        };
+        let result = export_svg(world, dir.join("merged"), options).await;
+        assert_eq!(result.unwrap(), dir.join("merged.svg"));
+        let merged = std::fs::read_to_string(dir.join("merged.svg")).unwrap();
+        assert_eq!(merged.matches("<svg").count(), 1);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
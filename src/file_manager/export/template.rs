@@ -1,27 +1,276 @@
 use crate::file_manager::export::errors::ExportError;
+use crate::file_manager::export::{compile_document, write_atomic};
+use crate::data::config::project::ProjectConfig;
+use crate::file_manager::template::{
+    copy_dir, PackageInfo, Template, TemplateInfo, TemplateManifest, MANIFEST_FILE_NAME,
+};
+use crate::world::TideWorld;
 use std::fs;
 use std::io::{Error, ErrorKind};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use typst::World;
+use typst_render::render;
 
-/// Exports a `.typ` template file by copying it to the specified output directory.
-/// This function reads the content of the given source file and writes it into the target
-/// directory, preserving the original file name.
+/// Version of the exported template packages.
+const TEMPLATE_VERSION: &str = "0.1.0";
+/// Directory of the package holding the scaffold of the template.
+const SCAFFOLD_DIR: &str = "template";
+/// File of the package holding the thumbnail of the template.
+const THUMBNAIL_FILE: &str = "thumbnail.png";
+/// Resolution of the thumbnails, in pixels per point.
+const THUMBNAIL_PIXEL_PER_PT: f32 = 1.0;
+
+/// Exports the project of `world` as a template package in the `templates` directory,
+/// on a blocking thread so that the other tasks keep running during the export.
+///
+/// The package is named after the project directory and replaces any template of the
+/// same name. The project files, except hidden ones, are copied as its scaffold, the
+/// main file of the project becomes its entrypoint, and the first page of the compiled
+/// document is rendered as its thumbnail when the project compiles.
 ///
-/// Returns the full path to the newly created file in the output directory upon success.
+/// Returns the path of the package directory.
 ///
 /// # Errors
 ///
-/// Returns an [`ExportError::FileReadError`] if reading the source file fails, or
-/// [`ExportError::FileWriteError`] if writing to the destination fails.
+/// Returns an [`ExportError::FileReadError`] if the project can't be read, or an
+/// [`ExportError::FileWriteError`] if the package can't be written.
 pub async fn export_template(
-    file_path: PathBuf,
-    output_path: PathBuf,
+    world: TideWorld,
+    templates: PathBuf,
+) -> Result<PathBuf, ExportError> {
+    match tokio::task::spawn_blocking(move || write_template(&world, &templates)).await {
+        Ok(result) => result,
+        Err(err) => std::panic::resume_unwind(err.into_panic()),
+    }
+}
+
+/// Writes the template package of the project of `world`, see [`export_template`].
+fn write_template(
+    world: &TideWorld,
+    templates: &Path,
+) -> Result<PathBuf, ExportError> {
+    let name = world
+        .root()
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .ok_or(ExportError::FileReadError(Error::new(
+            ErrorKind::InvalidInput,
+            "Can't read project name",
+        )))?;
+    let entrypoint = world
+        .main()
+        .vpath()
+        .as_rootless_path()
+        .to_string_lossy()
+        .replace('\\', "/");
+
+    //the package is built aside and replaces the previous one only once complete
+    let package = templates.join(&name);
+    let temp = templates.join(format!(".{name}.tide-tmp"));
+    let _ = fs::remove_dir_all(&temp); //left by an interrupted export
+    let result = write_package(world, &temp, name, entrypoint)
+        .and_then(|_| replace_dir(&temp, &package));
+    if result.is_err() {
+        let _ = fs::remove_dir_all(&temp);
+    }
+    result.map(|_| package)
+}
+
+/// Writes the scaffold, thumbnail and manifest of the template package `name`
+/// in the directory `package`.
+///
+/// # Errors
+///
+/// Returns an [`ExportError::FileReadError`] if the project can't be read, or an
+/// [`ExportError::FileWriteError`] if the package can't be written.
+fn write_package(
+    world: &TideWorld,
+    package: &Path,
+    name: String,
+    entrypoint: String,
+) -> Result<(), ExportError> {
+    copy_dir(world.root(), &package.join(SCAFFOLD_DIR)).map_err(|err| {
+        match err.kind() {
+            ErrorKind::NotFound => ExportError::FileReadError(err),
+            _ => ExportError::FileWriteError(err),
+        }
+    })?;
+
+    let thumbnail = write_thumbnail(world, &package.join(THUMBNAIL_FILE)).is_ok();
+    let manifest = TemplateManifest {
+        package: PackageInfo {
+            name,
+            version: String::from(TEMPLATE_VERSION),
+            entrypoint: format!("{SCAFFOLD_DIR}/{entrypoint}"),
+            description: None,
+        },
+        template: TemplateInfo {
+            path: String::from(SCAFFOLD_DIR),
+            entrypoint,
+            thumbnail: thumbnail.then(|| String::from(THUMBNAIL_FILE)),
+        },
+    };
+    let manifest = toml::to_string(&manifest).map_err(|err| {
+        ExportError::FileWriteError(Error::new(ErrorKind::InvalidData, err.to_string()))
+    })?;
+    write_atomic(&package.join(MANIFEST_FILE_NAME), manifest)
+}
+
+/// Replaces the directory `destination`, if any, by the directory `source`.
+///
+/// The previous directory is moved aside before the renaming and restored if it fails,
+/// so that `destination` is never left incomplete.
+///
+/// # Errors
+///
+/// Returns an [`ExportError::FileWriteError`] if a directory can't be renamed.
+fn replace_dir(
+    source: &Path,
+    destination: &Path,
+) -> Result<(), ExportError> {
+    let file_name = destination.file_name().unwrap_or_default().to_string_lossy();
+    let old = destination.with_file_name(format!(".{file_name}.tide-old"));
+    let _ = fs::remove_dir_all(&old);
+    if destination.exists() {
+        fs::rename(destination, &old).map_err(ExportError::FileWriteError)?;
+    }
+    if let Err(err) = fs::rename(source, destination) {
+        let _ = fs::rename(&old, destination);
+        return Err(ExportError::FileWriteError(err));
+    }
+    let _ = fs::remove_dir_all(&old);
+    Ok(())
+}
+
+/// Returns the path of a PNG thumbnail of `template`.
+///
+/// The thumbnail of the manifest is used if there is one. Otherwise, the first page of the
+/// template is rendered with the fonts and packages of `world` and cached in `cache`, where it
+/// is reused until the template is modified. The template is compiled and rendered
+/// on a blocking thread so that the other tasks keep running meanwhile.
+///
+/// # Errors
+///
+/// Returns a [`ExportError`] if the template can't be compiled or the thumbnail written.
+pub async fn render_thumbnail(
+    template: Template,
+    world: TideWorld,
+    cache: PathBuf,
 ) -> Result<PathBuf, ExportError> {
-    let file_content = fs::read(&file_path).map_err(|e| ExportError::FileReadError(e))?;
-    let output_path = output_path.join(file_path.file_name().ok_or(
-        ExportError::FileReadError(Error::new(ErrorKind::Other, "Can't read file name")),
-    )?);
-    fs::write(&output_path, file_content).map_err(|e| ExportError::FileWriteError(e))?;
+    let render = move || cached_thumbnail(&template, world, &cache);
+    match tokio::task::spawn_blocking(render).await {
+        Ok(result) => result,
+        Err(err) => std::panic::resume_unwind(err.into_panic()),
+    }
+}
+
+/// Returns the path of the thumbnail of `template`, see [`render_thumbnail`].
+fn cached_thumbnail(
+    template: &Template,
+    world: TideWorld,
+    cache: &Path,
+) -> Result<PathBuf, ExportError> {
+    if let Some(thumbnail) = template.thumbnail() {
+        return Ok(thumbnail);
+    }
+    let output = cached_thumbnail_path(cache, &template.path);
+    let cached = fs::metadata(&output).and_then(|metadata| metadata.modified());
+    if let (Ok(cached), Some(modified)) = (cached, template.modified()) {
+        if cached >= modified {
+            return Ok(output);
+        }
+    }
+
+    let root = template.scaffold_dir();
+    let main = TideWorld::id_from_path(&template.entrypoint(), &root).ok_or(
+        ExportError::FileReadError(Error::new(
+            ErrorKind::NotFound,
+            "Template entrypoint not found",
+        )),
+    )?;
+    let mut world = world;
+    world.change_root(root.clone());
+    world.change_main(main);
+    world.configure(&ProjectConfig::load(&root));
+    fs::create_dir_all(cache).map_err(ExportError::FileWriteError)?;
+    write_thumbnail(&world, &output)?;
+    Ok(output)
+}
+
+/// Returns the path, in `cache`, of the rendered thumbnail of the template at `template`.
+///
+/// See [`render_thumbnail`].
+pub fn cached_thumbnail_path(
+    cache: &Path,
+    template: &Path,
+) -> PathBuf {
+    let file_name = template.file_name().unwrap_or_default().to_string_lossy();
+    cache.join(format!("{file_name}.png"))
+}
+
+/// Renders the first page of the document of `world` as a PNG image at `path`.
+///
+/// # Errors
+///
+/// Returns a [`ExportError`] if compilation, encoding or writing fails,
+/// or if the document has no page.
+fn write_thumbnail(
+    world: &TideWorld,
+    path: &Path,
+) -> Result<(), ExportError> {
+    let document = compile_document(world)?.output;
+    let page = document.pages.first().ok_or(ExportError::NoPageSelected)?;
+    let png = render(page, THUMBNAIL_PIXEL_PER_PT)
+        .encode_png()
+        .map_err(|e| ExportError::PngEncodingError(e.to_string()))?;
+    write_atomic(path, png)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file_manager::template::Template;
+    use crate::world::tests::init_world;
+
+    #[tokio::test]
+    async fn test_export_template() {
+        let dir = std::env::temp_dir().join("tide-test-export-template");
+        let _ = fs::remove_dir_all(&dir);
+        let thesis = dir.join("thesis");
+        fs::create_dir_all(thesis.join("chapters")).unwrap();
+        fs::create_dir_all(thesis.join(".git")).unwrap();
+        fs::write(thesis.join("main.typ"), "#include \"chapters/intro.typ\"").unwrap();
+        fs::write(thesis.join("chapters").join("intro.typ"), "= Intro").unwrap();
+        let templates = dir.join("templates");
+        fs::create_dir_all(&templates).unwrap();
+
+        let main = TideWorld::id_from_path(&thesis.join("main.typ"), &thesis).unwrap();
+        let mut world = init_world();
+        world.change_root(thesis);
+        world.change_main(main);
+        fs::create_dir_all(templates.join("thesis").join("old")).unwrap();
+        let package = export_template(world, templates.clone()).await.unwrap();
+        assert!(!package.join("old").exists());
+        assert_eq!(fs::read_dir(&templates).unwrap().count(), 1);
+        let template = Template::load(&package).unwrap();
+        assert_eq!(template.manifest.package.name, "thesis");
+        assert_eq!(template.manifest.template.entrypoint, "main.typ");
+        assert!(package.join(THUMBNAIL_FILE).is_file());
+        assert!(!package.join(SCAFFOLD_DIR).join(".git").exists());
+
+        let project = dir.join("project");
+        let main = template.create_project(&project).unwrap();
+        assert_eq!(main, project.join("main.typ"));
+        assert!(project.join("chapters").join("intro.typ").is_file());
 
-    Ok(output_path)
+        let cache = dir.join("cache");
+        let thumbnail = render_thumbnail(template, init_world(), cache.clone()).await;
+        assert_eq!(thumbnail.unwrap(), package.join(THUMBNAIL_FILE));
+        let letter = dir.join("letter.typ");
+        fs::write(&letter, "Dear").unwrap();
+        let letter = Template::load(&letter).unwrap();
+        let thumbnail = render_thumbnail(letter, init_world(), cache.clone()).await;
+        assert_eq!(thumbnail.unwrap(), cache.join("letter.typ.png"));
+        let _ = fs::remove_dir_all(dir);
+    }
 }
@@ -122,6 +122,16 @@ pub fn get_fonts_path() -> Option<PathBuf> {
     None //something went wrong here because retrieve_env_path() only returns None when creating directories failed
 }
 
+/// Returns the path to the `thumbnails` directory inside the Tide config environment,
+/// where the rendered thumbnails of the templates are cached.
+///
+/// The directory is created when the thumbnails are written, so it may not exist yet.
+///
+/// Returns `None` if the environment is missing or invalid.
+pub fn get_thumbnails_path() -> Option<PathBuf> {
+    Some(retrieve_env_path()?.join("thumbnails"))
+}
+
 /// Returns the path to the user-defined `config.toml` file inside the Tide environment.
 ///
 /// This function does *not* create the file, it must be user-provided.
@@ -277,7 +287,6 @@ mod tests {
     use super::*;
     use iced::widget::text_editor::Content;
     use std::fmt::Debug;
-    use std::ops::Add;
     use typst::syntax::VirtualPath;
 
     macro_rules! fake_user {
@@ -437,10 +446,7 @@ mod tests {
     #[tokio::test]
     async fn test_save_disk() {
         assert!(create_test_file().await.is_ok());
-        assert!(check_file_saved(
-            TEST_FILE_NAME,
-            String::from(TEST_CONTENT).add("\n")
-        ));
+        assert!(check_file_saved(TEST_FILE_NAME, String::from(TEST_CONTENT)));
     }
 
     #[tokio::test]
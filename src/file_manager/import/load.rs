@@ -93,50 +93,3 @@ fn load_source_file(
         source,
     })
 }
-
-/// Recursively imports all compatible files from a given directory path.
-/// Only files with supported extensions (e.g. `.typ`, `.png`, `.svg`) are loaded and wrapped as [`ImportedFile`] variants.
-///
-/// # Errors
-///
-/// Returns an [`Error`] if any directory or file access fails during the traversal or import process.
-pub fn load_repo(
-    path: &PathBuf,
-    root: &PathBuf,
-) -> Result<Vec<ImportedFile>, Error> {
-    let files = recurse_compatible_files(path)?;
-    let mut imported_files = vec![];
-    for file in files {
-        if let Ok(imported_file) = load_file(&file, root) {
-            imported_files.push(imported_file);
-        }
-    }
-    Ok(imported_files)
-}
-
-/// Recursively gathers all file paths inside the provided directory.
-/// This function does not filter files by extension; all file paths are returned.
-///
-/// # Errors
-///
-/// Returns an [`Error`] if a directory or file cannot be accessed.
-fn recurse_compatible_files(path: &PathBuf) -> Result<Vec<PathBuf>, Error> {
-    let mut buf = vec![];
-    let entries = fs::read_dir(path)?;
-
-    for entry in entries {
-        let entry = entry?;
-        let meta = entry.metadata()?;
-
-        if meta.is_dir() {
-            let mut sub_dir = recurse_compatible_files(&entry.path())?;
-            buf.append(&mut sub_dir);
-        }
-
-        if meta.is_file() {
-            buf.push(entry.path());
-        }
-    }
-
-    Ok(buf)
-}
@@ -0,0 +1,451 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use toml_edit::{DocumentMut, Item, Value};
+
+/// Name of the manifest of a template package.
+pub const MANIFEST_FILE_NAME: &str = "typst.toml";
+
+/// Manifest of a template package, stored in its `typst.toml` file.
+///
+/// Follows the layout of the Typst packages providing a template:
+///
+/// ```toml
+/// [package]
+/// name = "thesis"
+/// version = "0.1.0"
+/// entrypoint = "template/main.typ"
+/// description = "A thesis with a bibliography"
+///
+/// [template]
+/// path = "template"
+/// entrypoint = "main.typ"
+/// thumbnail = "thumbnail.png"
+/// ```
+///
+/// Other fields of the Typst manifests are ignored.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TemplateManifest {
+    /// Information about the package.
+    pub package: PackageInfo,
+    /// Information about the template provided by the package.
+    pub template: TemplateInfo,
+}
+
+/// The `[package]` section of a [`TemplateManifest`].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PackageInfo {
+    /// Name of the package.
+    pub name: String,
+    /// Version of the package.
+    pub version: String,
+    /// Main file of the package, relative to its root.
+    pub entrypoint: String,
+    /// Short description of the package.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+/// The `[template]` section of a [`TemplateManifest`].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TemplateInfo {
+    /// Directory copied when creating a project, relative to the package root.
+    pub path: String,
+    /// Main file of the created projects, relative to the template directory.
+    pub entrypoint: String,
+    /// Image previewing the template, relative to the package root.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thumbnail: Option<String>,
+}
+
+/// A local template, from which new projects can be created.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Template {
+    /// Root directory of the template package, or the file of a single-file template.
+    pub path: PathBuf,
+    /// Manifest of the template.
+    pub manifest: TemplateManifest,
+}
+
+impl Template {
+    /// Loads the template at `path`.
+    ///
+    /// `path` can be the directory of a template package, its `typst.toml` manifest,
+    /// or a single `.typ` file, which is then both the scaffold and the entrypoint.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`io::Error`] if the manifest can't be read or doesn't describe a template.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let path = match path.file_name() {
+            Some(name) if name == MANIFEST_FILE_NAME => path.parent().unwrap_or(path),
+            _ => path,
+        };
+        if path.is_file() {
+            return Self::single_file(path);
+        }
+        let content = fs::read_to_string(path.join(MANIFEST_FILE_NAME))?;
+        let manifest = toml::from_str(&content)
+            .map_err(|err| io::Error::new(ErrorKind::InvalidData, err.to_string()))?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            manifest,
+        })
+    }
+
+    /// Creates a template made of the single `.typ` file at `path`.
+    fn single_file(path: &Path) -> io::Result<Self> {
+        let not_typst = || io::Error::new(ErrorKind::InvalidInput, "Not a Typst file");
+        if path.extension().is_none_or(|extension| extension != "typ") {
+            return Err(not_typst());
+        }
+        let file_name = path.file_name().ok_or_else(not_typst)?.to_string_lossy();
+        let name = path.file_stem().ok_or_else(not_typst)?.to_string_lossy();
+        Ok(Self {
+            path: path.to_path_buf(),
+            manifest: TemplateManifest {
+                package: PackageInfo {
+                    name: name.into_owned(),
+                    version: String::from("0.0.0"),
+                    entrypoint: file_name.to_string(),
+                    description: None,
+                },
+                template: TemplateInfo {
+                    path: String::from("."),
+                    entrypoint: file_name.into_owned(),
+                    thumbnail: None,
+                },
+            },
+        })
+    }
+
+    /// Returns the name of the template.
+    pub fn name(&self) -> &str {
+        &self.manifest.package.name
+    }
+
+    /// Returns the description of the template, if it has one.
+    pub fn description(&self) -> Option<&str> {
+        self.manifest.package.description.as_deref()
+    }
+
+    /// Returns the path of the thumbnail of the template, if it has one.
+    pub fn thumbnail(&self) -> Option<PathBuf> {
+        let thumbnail = self.manifest.template.thumbnail.as_ref()?;
+        Some(self.path.join(thumbnail)).filter(|path| path.is_file())
+    }
+
+    /// Returns the directory copied when creating a project from the template,
+    /// or the directory of a single-file template.
+    pub fn scaffold_dir(&self) -> PathBuf {
+        if self.path.is_file() {
+            return self.path.parent().unwrap_or(&self.path).to_path_buf();
+        }
+        self.path.join(&self.manifest.template.path)
+    }
+
+    /// Returns the path of the entrypoint of the template.
+    pub fn entrypoint(&self) -> PathBuf {
+        if self.path.is_file() {
+            return self.path.clone();
+        }
+        self.scaffold_dir().join(&self.manifest.template.entrypoint)
+    }
+
+    /// Returns the time of the last modification of the template, manifest included.
+    pub fn modified(&self) -> Option<SystemTime> {
+        last_modified(&self.path)
+    }
+
+    /// Renames the template, moving its file or directory within the templates directory.
+    ///
+    /// The `name` of the manifest of a template package is updated, other fields are kept.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`io::Error`] if the name is invalid or taken, or if moving fails.
+    pub fn rename(
+        &mut self,
+        name: &str,
+    ) -> io::Result<()> {
+        let path = self.sibling(name)?;
+        fs::rename(&self.path, &path)?;
+        if path.is_dir() {
+            write_package_name(&path, name)?;
+        }
+        *self = Self::load(&path)?;
+        Ok(())
+    }
+
+    /// Copies the template as a new template named `name`, next to it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`io::Error`] if the name is invalid or taken, or if copying fails.
+    pub fn duplicate(
+        &self,
+        name: &str,
+    ) -> io::Result<Self> {
+        let path = self.sibling(name)?;
+        if self.path.is_file() {
+            fs::copy(&self.path, &path)?;
+        } else {
+            copy_dir(&self.path, &path)?;
+            write_package_name(&path, name)?;
+        }
+        Self::load(&path)
+    }
+
+    /// Permanently deletes the template from the disk.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`io::Error`] if the file or directory can't be removed.
+    pub fn delete(self) -> io::Result<()> {
+        if self.path.is_file() {
+            fs::remove_file(&self.path)
+        } else {
+            fs::remove_dir_all(&self.path)
+        }
+    }
+
+    /// Returns the path of a template named `name` stored like this one, in the same directory.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`io::Error`] if the name is empty, hidden or a path,
+    /// or if a template already has this path.
+    fn sibling(
+        &self,
+        name: &str,
+    ) -> io::Result<PathBuf> {
+        let name = name.trim();
+        if name.is_empty() || name.starts_with('.') || name.contains(['/', '\\']) {
+            return Err(io::Error::new(
+                ErrorKind::InvalidInput,
+                "Invalid template name",
+            ));
+        }
+        let file_name = if self.path.is_file() {
+            format!("{name}.typ")
+        } else {
+            name.to_string()
+        };
+        let path = self.path.with_file_name(file_name);
+        if path.exists() {
+            return Err(io::Error::new(
+                ErrorKind::AlreadyExists,
+                "A template already has this name",
+            ));
+        }
+        Ok(path)
+    }
+
+    /// Copies the scaffold of the template into the directory `project`,
+    /// and returns the path of its entrypoint, to be used as main file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`io::Error`] if a file can't be copied, or if the entrypoint is missing.
+    pub fn create_project(
+        &self,
+        project: &Path,
+    ) -> io::Result<PathBuf> {
+        fs::create_dir_all(project)?;
+        if self.path.is_file() {
+            let main = project.join(&self.manifest.template.entrypoint);
+            fs::copy(&self.path, &main)?;
+            return Ok(main);
+        }
+        copy_dir(&self.path.join(&self.manifest.template.path), project)?;
+        let main = project.join(&self.manifest.template.entrypoint);
+        if !main.is_file() {
+            return Err(io::Error::new(
+                ErrorKind::NotFound,
+                format!("Template entrypoint {} not found", main.display()),
+            ));
+        }
+        Ok(main)
+    }
+}
+
+/// Returns the templates found in the directory `templates`, sorted by name.
+///
+/// Subdirectories whose manifest can't be read, files other than `.typ` files
+/// and hidden entries (such as packages being exported) are ignored.
+pub fn list_templates(templates: &Path) -> Vec<Template> {
+    let Ok(entries) = fs::read_dir(templates) else {
+        return vec![];
+    };
+    let mut templates: Vec<Template> = entries
+        .flatten()
+        .filter(|entry| !entry.file_name().to_string_lossy().starts_with('.'))
+        .filter_map(|entry| Template::load(&entry.path()).ok())
+        .collect();
+    templates.sort_by(|a, b| a.name().cmp(b.name()));
+    templates
+}
+
+/// Sets the `name` of the package in the manifest of the template package `dir`,
+/// keeping its other fields, their order and comments.
+fn write_package_name(
+    dir: &Path,
+    name: &str,
+) -> io::Result<()> {
+    let path = dir.join(MANIFEST_FILE_NAME);
+    let mut manifest: DocumentMut = fs::read_to_string(&path)?
+        .parse()
+        .map_err(|err| io::Error::new(ErrorKind::InvalidData, err))?;
+    let package_name = manifest
+        .get_mut("package")
+        .and_then(|package| package.get_mut("name"))
+        .and_then(Item::as_value_mut);
+    if let Some(package_name) = package_name {
+        let decor = package_name.decor().clone();
+        *package_name = Value::from(name);
+        *package_name.decor_mut() = decor;
+    }
+    fs::write(path, manifest.to_string())
+}
+
+/// Returns the time of the last modification of `path`, or of any file inside it.
+fn last_modified(path: &Path) -> Option<SystemTime> {
+    let modified = fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok();
+    let Ok(entries) = fs::read_dir(path) else {
+        return modified;
+    };
+    entries
+        .flatten()
+        .filter_map(|entry| last_modified(&entry.path()))
+        .chain(modified)
+        .max()
+}
+
+/// Recursively copies the content of the directory `source` into `destination`.
+///
+/// Hidden files and directories (e.g. `.git`) are skipped.
+///
+/// # Errors
+///
+/// Returns an [`io::Error`] if a directory can't be read or created, or a file can't be copied.
+pub fn copy_dir(
+    source: &Path,
+    destination: &Path,
+) -> io::Result<()> {
+    fs::create_dir_all(destination)?;
+    for entry in fs::read_dir(source)? {
+        let entry = entry?;
+        if entry.file_name().to_string_lossy().starts_with('.') {
+            continue;
+        }
+        let target = destination.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &target)?;
+        } else {
+            fs::copy(entry.path(), target)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_project() {
+        let dir = std::env::temp_dir().join("tide-test-template");
+        let _ = fs::remove_dir_all(&dir);
+        let package = dir.join("templates").join("thesis");
+        fs::create_dir_all(package.join("template").join("chapters")).unwrap();
+        fs::write(
+            package.join(MANIFEST_FILE_NAME),
+            "[package]\n\
+             name = \"thesis\"\n\
+             version = \"0.1.0\"\n\
+             entrypoint = \"lib.typ\"\n\
+             license = \"MIT\"\n\
+             [template]\n\
+             path = \"template\"\n\
+             entrypoint = \"thesis.typ\"\n",
+        )
+        .unwrap();
+        fs::write(package.join("template").join("thesis.typ"), "= Thesis").unwrap();
+        fs::write(
+            package.join("template").join("chapters").join("intro.typ"),
+            "Intro",
+        )
+        .unwrap();
+        fs::write(dir.join("templates").join("letter.typ"), "Dear").unwrap();
+        fs::write(dir.join("templates").join("notes.txt"), "").unwrap();
+
+        let thesis = Template::load(&package.join(MANIFEST_FILE_NAME)).unwrap();
+        assert_eq!(thesis.manifest.package.name, "thesis");
+        let project = dir.join("project");
+        let main = thesis.create_project(&project).unwrap();
+        assert_eq!(main, project.join("thesis.typ"));
+        assert!(project.join("chapters").join("intro.typ").is_file());
+
+        let letter = Template::load(&dir.join("templates").join("letter.typ")).unwrap();
+        assert!(Template::load(&dir.join("templates").join("notes.txt")).is_err());
+        let main = letter.create_project(&dir.join("letter")).unwrap();
+        assert_eq!(fs::read_to_string(main).unwrap(), "Dear");
+        let templates = list_templates(&dir.join("templates"));
+        let names: Vec<_> = templates.iter().map(Template::name).collect();
+        assert_eq!(names, ["letter", "thesis"]);
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_manage_templates() {
+        let dir = std::env::temp_dir().join("tide-test-manage-templates");
+        let _ = fs::remove_dir_all(&dir);
+        let package = dir.join("report");
+        fs::create_dir_all(package.join("template")).unwrap();
+        fs::write(
+            package.join(MANIFEST_FILE_NAME),
+            "[package]\n\
+             name = \"report\"\n\
+             version = \"0.1.0\"\n\
+             entrypoint = \"lib.typ\"\n\
+             # kept by the renaming\n\
+             authors = [\"Tide\"]\n\
+             [template]\n\
+             path = \"template\"\n\
+             entrypoint = \"main.typ\"\n",
+        )
+        .unwrap();
+        fs::write(package.join("template").join("main.typ"), "= Report").unwrap();
+        fs::write(dir.join("memo.typ"), "Memo").unwrap();
+
+        let mut report = Template::load(&package).unwrap();
+        assert!(report.rename("../report").is_err());
+        assert!(report.duplicate("report-copy").is_ok());
+        report.rename("weekly").unwrap();
+        assert_eq!(report.path, dir.join("weekly"));
+        assert_eq!(report.name(), "weekly");
+        let manifest = fs::read_to_string(dir.join("weekly").join(MANIFEST_FILE_NAME));
+        assert!(manifest.unwrap().starts_with(
+            "[package]\nname = \"weekly\"\nversion = \"0.1.0\"\n\
+             entrypoint = \"lib.typ\"\n# kept by the renaming\nauthors"
+        ));
+
+        let memo = Template::load(&dir.join("memo.typ")).unwrap();
+        assert!(memo.duplicate("memo").is_err());
+        let copy = memo.duplicate("memo-copy").unwrap();
+        assert_eq!(copy.entrypoint(), dir.join("memo-copy.typ"));
+        copy.delete().unwrap();
+        report.delete().unwrap();
+
+        let names: Vec<_> = list_templates(&dir)
+            .iter()
+            .map(|template| template.name().to_string())
+            .collect();
+        assert_eq!(names, ["memo", "report-copy"]);
+        let _ = fs::remove_dir_all(dir);
+    }
+}
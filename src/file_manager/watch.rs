@@ -0,0 +1,133 @@
+use iced::futures::channel::mpsc;
+use iced::futures::{SinkExt, Stream, StreamExt};
+use iced::{stream, Subscription};
+use notify::event::ModifyKind;
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+use std::path::{Component, Path, PathBuf};
+
+/// Paths modified on disk by a single event of the file system.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Changes {
+    /// Paths affected by the event.
+    pub paths: Vec<PathBuf>,
+    /// Whether entries were created, removed or renamed, changing the file tree.
+    pub entries_changed: bool,
+}
+
+/// Returns a [`Subscription`] emitting the [`Changes`] on disk inside the directory `root`.
+///
+/// The directory is watched recursively. Hidden files and directories (such as `.git`)
+/// are ignored, as well as events that don't change the content of the directory.
+pub fn watch_directory(root: PathBuf) -> Subscription<Changes> {
+    Subscription::run_with(root, watch)
+}
+
+/// Creates the [`Stream`] of changed paths behind [`watch_directory`].
+///
+/// The stream ends if the watcher can't be created or the root can't be watched.
+#[allow(clippy::ptr_arg)] // the signature is imposed by `Subscription::run_with`
+fn watch(root: &PathBuf) -> impl Stream<Item = Changes> {
+    let root = root.clone();
+    stream::channel(100, async move |mut output| {
+        let (sender, mut receiver) = mpsc::unbounded();
+        let watcher = notify::recommended_watcher(move |event: notify::Result<Event>| {
+            if let Ok(event) = event {
+                let _ = sender.unbounded_send(event);
+            }
+        });
+        let mut watcher = match watcher {
+            Ok(watcher) => watcher,
+            Err(err) => {
+                eprintln!("can't create the file watcher: {err}");
+                return;
+            }
+        };
+        if let Err(err) = watcher.watch(&root, RecursiveMode::Recursive) {
+            eprintln!("can't watch {:?}: {err}", root);
+            return;
+        }
+
+        while let Some(event) = receiver.next().await {
+            let entries_changed = changes_entries(&event.kind);
+            let paths = changed_paths(&root, event);
+            if paths.is_empty() {
+                continue;
+            }
+            let changes = Changes {
+                paths,
+                entries_changed,
+            };
+            if output.send(changes).await.is_err() {
+                break;
+            }
+        }
+    })
+}
+
+/// Returns the paths inside `root` affected by `event`.
+fn changed_paths(
+    root: &Path,
+    event: Event,
+) -> Vec<PathBuf> {
+    match event.kind {
+        EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_) => event
+            .paths
+            .into_iter()
+            .filter(|path| !is_hidden(root, path))
+            .collect(),
+        _ => vec![],
+    }
+}
+
+/// Returns `true` if an event of this kind creates, removes or renames entries.
+fn changes_entries(kind: &EventKind) -> bool {
+    matches!(
+        kind,
+        EventKind::Create(_)
+            | EventKind::Remove(_)
+            | EventKind::Modify(ModifyKind::Name(_))
+    )
+}
+
+/// Returns `true` if `path` is inside a hidden file or directory of `root`.
+fn is_hidden(
+    root: &Path,
+    path: &Path,
+) -> bool {
+    path.strip_prefix(root).is_ok_and(|relative| {
+        relative.components().any(|component| match component {
+            Component::Normal(name) => name.to_string_lossy().starts_with('.'),
+            _ => false,
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use notify::event::{AccessKind, CreateKind, DataChange, RenameMode};
+
+    #[test]
+    fn test_changed_paths() {
+        let root = PathBuf::from("/project");
+        let event = Event::new(EventKind::Create(CreateKind::File))
+            .add_path(root.join("main.typ"))
+            .add_path(root.join(".git").join("index"));
+        assert_eq!(changed_paths(&root, event), vec![root.join("main.typ")]);
+
+        let event = Event::new(EventKind::Access(AccessKind::Any))
+            .add_path(root.join("main.typ"));
+        assert!(changed_paths(&root, event).is_empty());
+    }
+
+    #[test]
+    fn test_changes_entries() {
+        assert!(changes_entries(&EventKind::Create(CreateKind::File)));
+        assert!(changes_entries(&EventKind::Modify(ModifyKind::Name(
+            RenameMode::Both
+        ))));
+        assert!(!changes_entries(&EventKind::Modify(ModifyKind::Data(
+            DataChange::Content
+        ))));
+    }
+}
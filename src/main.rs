@@ -6,8 +6,9 @@ use font::{
     EDITOR_SEMI_BOLD_BYTES,
 };
 use iced::Font;
-use iced::{widget::container, Element, Settings, Task, Theme};
+use iced::{widget::container, Element, Settings, Subscription, Task, Theme};
 
+mod cli;
 mod data;
 mod editor;
 mod file_manager;
@@ -29,13 +30,25 @@ use screen::{
 
 /// Entry point of the Tide application.
 ///
-/// Loads configuration from a TOML file and launches the application using `iced::application`.
+/// Runs the given command-line command without a display, if any (see [`cli::USAGE`]).
+/// Otherwise, loads configuration from a TOML file and launches the application
+/// using `iced::application`.
 fn main() -> iced::Result {
+    match cli::parse_args(std::env::args().skip(1)) {
+        Ok(Some(command)) => std::process::exit(cli::run(command)),
+        Ok(None) => {}
+        Err(err) => {
+            eprintln!("error: {err}\n\n{}", cli::USAGE);
+            std::process::exit(cli::EXIT_USAGE);
+        }
+    }
+
     let config = Config::load(get_config_path());
     let settings = settings(&config.general);
 
     iced::application(move || Tide::new(config.clone()), Tide::update, Tide::view)
         .settings(settings)
+        .subscription(Tide::subscription)
         .theme(Tide::theme)
         .resizable(true)
         .transparent(true)
@@ -64,7 +77,7 @@ impl Tide {
     fn new(config: Config) -> (Self, Task<Message>) {
         (
             Self {
-                screen: Screen::Welcome(Welcome::new()),
+                screen: Screen::Welcome(Welcome::new(config.typst.clone())),
                 theme: Theme::from(&config.colors),
                 window_scale_factor: config.general.window_scale_factor,
                 config,
@@ -112,6 +125,7 @@ impl Tide {
                                 Some(import_path) => {
                                     self.screen = Screen::Editing(Editing::new(
                                         self.config.editor.clone(),
+                                        self.config.typst.clone(),
                                         import_path.to_path_buf(),
                                     ));
                                     Task::done(Message::Editor(
@@ -129,6 +143,7 @@ impl Tide {
                                         Some(import_path) => {
                                             self.screen = Screen::Editing(Editing::new(
                                                 self.config.editor.clone(),
+                                                self.config.typst.clone(),
                                                 import_path.to_path_buf(),
                                             ));
                                             Task::done(Message::Editor(
@@ -152,6 +167,9 @@ impl Tide {
                     welcome::Message::ProjectModal(message) => welcome
                         .update(welcome::Message::ProjectModal(message))
                         .map(Message::Welcome),
+                    welcome::Message::TemplateGallery(message) => welcome
+                        .update(welcome::Message::TemplateGallery(message))
+                        .map(Message::Welcome),
                 }
             }
         }
@@ -168,6 +186,16 @@ impl Tide {
         container(screen).into()
     }
 
+    /// Returns the subscriptions of the active screen.
+    ///
+    /// Only the editor listens to external events, such as changes to the project files.
+    fn subscription(&self) -> Subscription<Message> {
+        match &self.screen {
+            Screen::Editing(editing) => editing.subscription().map(Message::Editor),
+            Screen::Welcome(_) => Subscription::none(),
+        }
+    }
+
     /// Returns the current UI theme.
     fn theme(&self) -> Theme {
         self.theme.clone()
@@ -1,5 +1,9 @@
+pub mod export_options;
 pub mod file_tree;
+pub mod font_manager;
 pub mod modal;
 pub mod pop_up;
 pub mod preview;
+pub mod project_settings;
+pub mod template_gallery;
 pub mod toolbar;
@@ -0,0 +1,678 @@
+use crate::data::config::preset::{ExportPreset, PresetFormat};
+use crate::data::config::project::{PdfConfig, ProjectConfig, DATE_FORMAT};
+use crate::data::style::button::{cancel_button, validate_button};
+use crate::data::style::modal::{modal_style, modal_text_style};
+use crate::file_manager::export::parse_page_ranges;
+use crate::file_manager::export::pdf::{
+    pdf_standard_name, pdf_standards, PdfExportOptions, PDF_SUBSTANDARDS, PDF_VERSIONS,
+};
+use crate::file_manager::export::png::{PngBackground, PngExportOptions, DEFAULT_PPI};
+use crate::file_manager::export::svg::{
+    SvgExportOptions, DEFAULT_SVG_GAP, DEFAULT_SVG_PATTERN,
+};
+use crate::screen::editing;
+use chrono::{NaiveDate, Utc};
+use iced::widget::{
+    button, center, checkbox, column, container, opaque, pick_list, radio, row, space,
+    text, text_input, Column,
+};
+use iced::{Alignment, Element, Length, Task};
+use std::fmt::{Display, Formatter};
+use typst::visualize::Color;
+use typst_pdf::PdfStandard;
+
+const MODAL_WIDTH: f32 = 500.0;
+const MODAL_HEIGHT: f32 = 560.0;
+const MODAL_BUTTON_HEIGHT: f32 = 30.0;
+const MODAL_BUTTON_WIDTH: f32 = 80.0;
+const SPACING: f32 = 10.0;
+const PRESET_NAME_WIDTH: f32 = 140.0;
+
+/// Placeholders of the naming pattern of SVG files, see [`page_file_name`].
+///
+/// [`page_file_name`]: crate::file_manager::export::svg::page_file_name
+const PATTERN_HELP: &str = "{name}: chosen name, {i}: page index, {p}: page number, \
+                            {0p}: padded page number, {t}: page count";
+/// Effects of the build date of the project, which isn't specific to the PDF.
+const DATE_HELP: &str = "Creation date of the PDF, and the date of datetime.today() \
+                         once saved as project default";
+
+/// Format whose options are edited in the modal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    /// See [`PdfExportOptions`].
+    Pdf,
+    /// See [`PngExportOptions`].
+    Png,
+    /// See [`SvgExportOptions`].
+    Svg,
+}
+
+/// Kind of background chosen for a PNG export.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Background {
+    /// See [`PngBackground::Document`].
+    Document,
+    /// See [`PngBackground::Transparent`].
+    Transparent,
+    /// See [`PngBackground::Fill`].
+    Fill,
+}
+
+/// An optional PDF standard, as listed in a pick list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Standard(Option<PdfStandard>);
+
+impl Display for Standard {
+    fn fmt(
+        &self,
+        f: &mut Formatter<'_>,
+    ) -> std::fmt::Result {
+        match self.0 {
+            Some(standard) => write!(f, "{}", pdf_standard_name(standard)),
+            None => write!(f, "None"),
+        }
+    }
+}
+
+/// Messages used in the context of the export options modal.
+#[derive(Debug, Clone)]
+pub enum Message {
+    /// Triggered when the page ranges input changes.
+    Pages(String),
+    /// Triggered when the user chooses a PDF version.
+    Version(Standard),
+    /// Triggered when the user chooses a PDF substandard (PDF/A or PDF/UA).
+    Substandard(Standard),
+    /// Triggered when the tagged PDF checkbox is toggled.
+    Tagged(bool),
+    /// Triggered when the PDF identifier input changes.
+    Ident(String),
+    /// Triggered when the project build date input changes.
+    Date(String),
+    /// Triggered when the "save as project defaults" checkbox is toggled.
+    SaveDefaults(bool),
+    /// Triggered when the resolution input changes.
+    Ppi(String),
+    /// Triggered when the user chooses a kind of background.
+    Background(Background),
+    /// Triggered when the fill color input changes.
+    FillColor(String),
+    /// Triggered when the SVG naming pattern input changes.
+    Pattern(String),
+    /// Triggered when the merged SVG checkbox is toggled.
+    Merged(bool),
+    /// Triggered when the gap between merged SVG pages input changes.
+    Gap(String),
+    /// Triggered when the preset name input changes.
+    PresetName(String),
+    /// Triggered when the user saves the options as a preset of the project.
+    SavePreset,
+    /// Triggered when the user confirms the export.
+    Export,
+    /// Triggered when the modal is canceled.
+    Cancel,
+}
+
+/// A modal dialog used to choose the options of a PDF, PNG or SVG export.
+///
+/// PDF options are filled from the project configuration, and can be saved back to it.
+/// PNG and SVG options keep their values between two exports.
+/// The options of any format can be saved as a named preset of the project.
+pub struct ExportOptions {
+    /// The modal visibility state.
+    pub visible: bool,
+    /// Format of the export.
+    format: Format,
+    /// Configuration of the project when the modal was opened.
+    project: ProjectConfig,
+    /// Page ranges of the PDF export, empty for every page.
+    pdf_pages: String,
+    /// PDF version to conform to.
+    version: Standard,
+    /// PDF/A or PDF/UA substandard to conform to.
+    substandard: Standard,
+    /// Whether to write a tagged PDF.
+    tagged: bool,
+    /// PDF identifier, empty for the default one.
+    ident: String,
+    /// Default PDF identifier, shown as placeholder.
+    default_ident: String,
+    /// Build date of the project, also the creation date of the PDF,
+    /// empty for the current time.
+    date: String,
+    /// Whether to save the PDF options as defaults of the project.
+    save_defaults: bool,
+    /// Page ranges of the PNG export, empty for every page.
+    png_pages: String,
+    /// Resolution in pixels per inch.
+    ppi: String,
+    /// Kind of background of the images.
+    background: Background,
+    /// Hexadecimal fill color, used with [`Background::Fill`].
+    fill_color: String,
+    /// Page ranges of the SVG export, empty for every page.
+    svg_pages: String,
+    /// Naming pattern of the SVG files.
+    pattern: String,
+    /// Whether to export a single SVG file.
+    merged: bool,
+    /// Gap between the pages of a merged SVG, in points.
+    gap: String,
+    /// Name of the preset to save the options as.
+    preset_name: String,
+    /// Warning text to show in case of invalid input.
+    warning_text: String,
+}
+
+impl ExportOptions {
+    /// Creates a new, hidden, [`ExportOptions`] modal filled with the default options.
+    pub fn new() -> Self {
+        Self {
+            visible: false,
+            format: Format::Pdf,
+            project: ProjectConfig::default(),
+            pdf_pages: String::new(),
+            version: Standard(None),
+            substandard: Standard(None),
+            tagged: true,
+            ident: String::new(),
+            default_ident: String::new(),
+            date: String::new(),
+            save_defaults: false,
+            png_pages: String::new(),
+            ppi: DEFAULT_PPI.to_string(),
+            background: Background::Document,
+            fill_color: String::from("#ffffff"),
+            svg_pages: String::new(),
+            pattern: String::from(DEFAULT_SVG_PATTERN),
+            merged: false,
+            gap: DEFAULT_SVG_GAP.to_string(),
+            preset_name: String::new(),
+            warning_text: String::new(),
+        }
+    }
+
+    /// Makes the modal visible with the PDF options of the given project.
+    ///
+    /// `default_ident` is the identifier used when the project doesn't define one.
+    pub fn show_pdf(
+        &mut self,
+        project: &ProjectConfig,
+        default_ident: String,
+    ) {
+        let pdf = &project.pdf;
+        let find = |list: &[(PdfStandard, &str)]| {
+            Standard(
+                pdf.standards
+                    .iter()
+                    .copied()
+                    .find(|standard| list.iter().any(|(known, _)| known == standard)),
+            )
+        };
+        self.version = find(&PDF_VERSIONS);
+        self.substandard = find(&PDF_SUBSTANDARDS);
+        self.tagged = pdf.tagged;
+        self.ident = pdf.ident.clone().unwrap_or_default();
+        self.default_ident = default_ident;
+        self.pdf_pages = pdf.pages.clone().unwrap_or_default();
+        self.date = project.build.date.clone().unwrap_or_default();
+        self.save_defaults = false;
+        self.project = project.clone();
+        self.show(Format::Pdf);
+    }
+
+    /// Makes the modal visible with the last PNG options.
+    pub fn show_png(
+        &mut self,
+        project: &ProjectConfig,
+    ) {
+        self.project = project.clone();
+        self.show(Format::Png);
+    }
+
+    /// Makes the modal visible with the last SVG options.
+    pub fn show_svg(
+        &mut self,
+        project: &ProjectConfig,
+    ) {
+        self.project = project.clone();
+        self.show(Format::Svg);
+    }
+
+    /// Makes the modal visible for the given format.
+    fn show(
+        &mut self,
+        format: Format,
+    ) {
+        self.format = format;
+        self.warning_text = String::new();
+        self.visible = true;
+    }
+
+    /// Makes the modal invisible.
+    fn hide(&mut self) {
+        self.visible = false;
+    }
+
+    /// Builds the PDF export options and the matching project configuration
+    /// from the fields of the modal.
+    ///
+    /// # Errors
+    ///
+    /// Returns a warning text if a field is invalid.
+    fn to_pdf_options(&self) -> Result<(PdfExportOptions, ProjectConfig), String> {
+        let mut project = self.project.clone();
+        let optional = |field: &str| {
+            let field = field.trim();
+            (!field.is_empty()).then(|| field.to_owned())
+        };
+
+        let standards: Vec<PdfStandard> = [self.version.0, self.substandard.0]
+            .into_iter()
+            .flatten()
+            .collect();
+        pdf_standards(&standards)?;
+        project.pdf = PdfConfig {
+            standards,
+            tagged: self.tagged,
+            ident: optional(&self.ident),
+            pages: optional(&self.pdf_pages),
+            ..project.pdf
+        };
+        let pages = parse_page_ranges(&self.pdf_pages)?;
+
+        project.build.date = optional(&self.date);
+        if let Some(date) = &project.build.date {
+            NaiveDate::parse_from_str(date, DATE_FORMAT)
+                .map_err(|_| String::from("Date must be formatted as YYYY-MM-DD"))?;
+        }
+
+        let options = PdfExportOptions {
+            ident: Some(
+                project
+                    .pdf
+                    .ident
+                    .clone()
+                    .unwrap_or_else(|| self.default_ident.clone()),
+            ),
+            timestamp: Some(project.build.timestamp().unwrap_or_else(Utc::now)),
+            pages,
+            standards: project.pdf.standards.clone(),
+            tagged: project.pdf.tagged,
+        };
+        Ok((options, project))
+    }
+
+    /// Builds the PNG export options from the fields of the modal.
+    ///
+    /// # Errors
+    ///
+    /// Returns a warning text if a field is invalid.
+    fn to_png_options(&self) -> Result<PngExportOptions, String> {
+        let ppi = match self.ppi.trim().parse::<f32>() {
+            Ok(ppi) if ppi > 0.0 => ppi,
+            _ => return Err(String::from("Resolution must be a positive number")),
+        };
+        let pages = parse_page_ranges(&self.png_pages)?;
+        let background = match self.background {
+            Background::Document => PngBackground::Document,
+            Background::Transparent => PngBackground::Transparent,
+            Background::Fill => PngBackground::Fill(
+                self.fill_color
+                    .trim()
+                    .parse::<Color>()
+                    .map_err(|err| format!("Invalid fill color: {err}"))?,
+            ),
+        };
+        Ok(PngExportOptions {
+            ppi,
+            pages,
+            background,
+        })
+    }
+
+    /// Builds the SVG export options from the fields of the modal.
+    ///
+    /// # Errors
+    ///
+    /// Returns a warning text if a field is invalid.
+    fn to_svg_options(&self) -> Result<SvgExportOptions, String> {
+        let pages = parse_page_ranges(&self.svg_pages)?;
+        let gap = match self.gap.trim().parse::<f64>() {
+            Ok(gap) if gap >= 0.0 => gap,
+            _ => return Err(String::from("Gap must be a positive number")),
+        };
+        let pattern = self.pattern.trim();
+        if !self.merged
+            && !["{i}", "{p}", "{0p}"]
+                .iter()
+                .any(|page| pattern.contains(page))
+        {
+            return Err(String::from(
+                "Naming pattern must contain the page ({i}, {p} or {0p})",
+            ));
+        }
+        Ok(SvgExportOptions {
+            pattern: pattern.to_owned(),
+            pages,
+            merged: self.merged,
+            gap,
+        })
+    }
+
+    /// Builds a preset named after the preset name input from the fields of the modal.
+    ///
+    /// The preset keeps the output of the preset of the project with the same name,
+    /// if any, and writes to `out/{main}.<extension>` otherwise.
+    ///
+    /// # Errors
+    ///
+    /// Returns a warning text if the name is empty or a field is invalid.
+    fn to_preset(&self) -> Result<ExportPreset, String> {
+        let name = self.preset_name.trim();
+        if name.is_empty() {
+            return Err(String::from("Preset name can't be empty"));
+        }
+        let (pages, format) = match self.format {
+            Format::Pdf => {
+                let (_, project) = self.to_pdf_options()?;
+                let format = PresetFormat::Pdf {
+                    standards: project.pdf.standards,
+                    tagged: project.pdf.tagged,
+                };
+                (&self.pdf_pages, format)
+            }
+            Format::Png => {
+                let options = self.to_png_options()?;
+                let background = match self.background {
+                    Background::Document => String::from("document"),
+                    Background::Transparent => String::from("transparent"),
+                    Background::Fill => self.fill_color.trim().to_owned(),
+                };
+                let format = PresetFormat::Png {
+                    ppi: options.ppi,
+                    background,
+                };
+                (&self.png_pages, format)
+            }
+            Format::Svg => {
+                let options = self.to_svg_options()?;
+                let format = PresetFormat::Svg {
+                    merged: options.merged,
+                    gap: options.gap,
+                    pattern: options.pattern,
+                };
+                (&self.svg_pages, format)
+            }
+        };
+        let output = self
+            .project
+            .presets
+            .iter()
+            .find(|preset| preset.name == name)
+            .map(|preset| preset.output.clone())
+            .unwrap_or_else(|| format!("out/{{main}}.{}", format.extension()));
+        Ok(ExportPreset {
+            name: name.to_owned(),
+            output,
+            pages: (!pages.trim().is_empty()).then(|| pages.trim().to_owned()),
+            format,
+        })
+    }
+
+    /// Returns the Iced view for the modal.
+    ///
+    /// Includes the fields of the options of the current format,
+    /// and buttons to export or cancel.
+    pub fn view(&self) -> Element<'_, Message> {
+        let (title, fields) = match self.format {
+            Format::Pdf => ("Export as PDF", self.view_pdf()),
+            Format::Png => ("Export as PNG", self.view_png()),
+            Format::Svg => ("Export as SVG", self.view_svg()),
+        };
+
+        let modal_content = container(
+            column![
+                text(title).size(20),
+                fields,
+                space().height(Length::Fill),
+                text(self.warning_text.clone())
+                    .size(15)
+                    .style(modal_text_style),
+                row![
+                    text_input("Preset name", &self.preset_name)
+                        .on_input(Message::PresetName)
+                        .width(PRESET_NAME_WIDTH),
+                    button(text("Save preset").center())
+                        .on_press(Message::SavePreset)
+                        .height(MODAL_BUTTON_HEIGHT),
+                    space().width(Length::Fill),
+                    button(text("Export").center())
+                        .on_press(Message::Export)
+                        .height(MODAL_BUTTON_HEIGHT)
+                        .width(MODAL_BUTTON_WIDTH)
+                        .style(validate_button),
+                    button(text("Cancel").center())
+                        .on_press(Message::Cancel)
+                        .height(MODAL_BUTTON_HEIGHT)
+                        .width(MODAL_BUTTON_WIDTH)
+                        .style(cancel_button),
+                ]
+                .spacing(SPACING),
+            ]
+            .spacing(SPACING),
+        )
+        .width(MODAL_WIDTH)
+        .height(MODAL_HEIGHT)
+        .padding(20)
+        .style(modal_style);
+
+        opaque(center(opaque(modal_content)))
+    }
+
+    /// Returns the fields of the PDF options.
+    fn view_pdf(&self) -> Column<'_, Message> {
+        let choices = |list: &[(PdfStandard, &str)]| {
+            std::iter::once(Standard(None))
+                .chain(list.iter().map(|(standard, _)| Standard(Some(*standard))))
+                .collect::<Vec<_>>()
+        };
+
+        column![
+            text("Standards"),
+            row![
+                pick_list(choices(&PDF_VERSIONS), Some(self.version), Message::Version),
+                pick_list(
+                    choices(&PDF_SUBSTANDARDS),
+                    Some(self.substandard),
+                    Message::Substandard
+                ),
+            ]
+            .spacing(SPACING),
+            checkbox(self.tagged)
+                .label("Tagged PDF (accessibility)")
+                .on_toggle(Message::Tagged),
+            text("Pages"),
+            text_input("All pages, or ranges (e.g. 1-3,5)", &self.pdf_pages)
+                .on_input(Message::Pages),
+            text("Identifier"),
+            text_input(&self.default_ident, &self.ident).on_input(Message::Ident),
+            text("Project build date"),
+            text_input("Current date, or YYYY-MM-DD", &self.date).on_input(Message::Date),
+            text(DATE_HELP).size(13),
+            checkbox(self.save_defaults)
+                .label("Save as project defaults")
+                .on_toggle(Message::SaveDefaults),
+        ]
+        .spacing(SPACING)
+    }
+
+    /// Returns the fields of the PNG options.
+    fn view_png(&self) -> Column<'_, Message> {
+        let background = |label, value| {
+            radio(label, value, Some(self.background), Message::Background)
+        };
+
+        column![
+            text("Resolution (pixels per inch)"),
+            text_input("e.g. 144", &self.ppi).on_input(Message::Ppi),
+            text("Pages"),
+            text_input("All pages, or ranges (e.g. 1-3,5)", &self.png_pages)
+                .on_input(Message::Pages),
+            text("Background"),
+            row![
+                background("Document", Background::Document),
+                background("Transparent", Background::Transparent),
+                background("Color", Background::Fill),
+                text_input("#ffffff", &self.fill_color).on_input(Message::FillColor),
+            ]
+            .spacing(SPACING * 2.0)
+            .align_y(Alignment::Center),
+        ]
+        .spacing(SPACING)
+    }
+
+    /// Returns the fields of the SVG options.
+    fn view_svg(&self) -> Column<'_, Message> {
+        let mode: Element<'_, Message> = if self.merged {
+            column![
+                text("Gap between pages (pt)"),
+                text_input("e.g. 10", &self.gap).on_input(Message::Gap),
+            ]
+            .spacing(SPACING)
+            .into()
+        } else {
+            column![
+                text("File names"),
+                text_input(DEFAULT_SVG_PATTERN, &self.pattern).on_input(Message::Pattern),
+                text(PATTERN_HELP).size(13),
+            ]
+            .spacing(SPACING)
+            .into()
+        };
+
+        column![
+            text("Pages"),
+            text_input("All pages, or ranges (e.g. 1-3,5)", &self.svg_pages)
+                .on_input(Message::Pages),
+            checkbox(self.merged)
+                .label("Merge the pages in a single SVG")
+                .on_toggle(Message::Merged),
+            mode,
+        ]
+        .spacing(SPACING)
+    }
+
+    /// Handles messages to update the internal state.
+    ///
+    /// Confirming valid options sends them to the editing screen, which asks for the output path.
+    /// If requested, the PDF options are also applied to the project configuration.
+    pub fn update(
+        &mut self,
+        message: Message,
+    ) -> Task<editing::Message> {
+        match message {
+            Message::Pages(pages) => match self.format {
+                Format::Pdf => self.pdf_pages = pages,
+                Format::Png => self.png_pages = pages,
+                Format::Svg => self.svg_pages = pages,
+            },
+            Message::Version(version) => {
+                self.version = version;
+            }
+            Message::Substandard(substandard) => {
+                self.substandard = substandard;
+            }
+            Message::Tagged(tagged) => {
+                self.tagged = tagged;
+            }
+            Message::Ident(ident) => {
+                self.ident = ident;
+            }
+            Message::Date(date) => {
+                self.date = date;
+            }
+            Message::SaveDefaults(save_defaults) => {
+                self.save_defaults = save_defaults;
+            }
+            Message::Ppi(ppi) => {
+                self.ppi = ppi;
+            }
+            Message::Background(background) => {
+                self.background = background;
+            }
+            Message::FillColor(color) => {
+                self.fill_color = color;
+            }
+            Message::Pattern(pattern) => {
+                self.pattern = pattern;
+            }
+            Message::Merged(merged) => {
+                self.merged = merged;
+            }
+            Message::Gap(gap) => {
+                self.gap = gap;
+            }
+            Message::PresetName(name) => {
+                self.preset_name = name;
+            }
+            Message::SavePreset => match self.to_preset() {
+                Ok(preset) => {
+                    let mut project = self.project.clone();
+                    match project.presets.iter_mut().find(|p| p.name == preset.name) {
+                        Some(existing) => *existing = preset,
+                        None => project.presets.push(preset),
+                    }
+                    self.hide();
+                    return Task::done(editing::Message::ProjectConfigured(Box::new(
+                        project,
+                    )));
+                }
+                Err(warning) => {
+                    self.warning_text = warning;
+                }
+            },
+            Message::Export => match self.format {
+                Format::Pdf => match self.to_pdf_options() {
+                    Ok((options, project)) => {
+                        self.hide();
+                        let export = Task::done(editing::Message::ExportPdf(options));
+                        if self.save_defaults {
+                            return Task::done(editing::Message::ProjectConfigured(
+                                Box::new(project),
+                            ))
+                            .chain(export);
+                        }
+                        return export;
+                    }
+                    Err(warning) => {
+                        self.warning_text = warning;
+                    }
+                },
+                Format::Png => match self.to_png_options() {
+                    Ok(options) => {
+                        self.hide();
+                        return Task::done(editing::Message::ExportPng(options));
+                    }
+                    Err(warning) => {
+                        self.warning_text = warning;
+                    }
+                },
+                Format::Svg => match self.to_svg_options() {
+                    Ok(options) => {
+                        self.hide();
+                        return Task::done(editing::Message::ExportSvg(options));
+                    }
+                    Err(warning) => {
+                        self.warning_text = warning;
+                    }
+                },
+            },
+            Message::Cancel => {
+                self.hide();
+            }
+        }
+        Task::none()
+    }
+}
@@ -133,6 +133,16 @@ impl FileTree {
     ) {
         self.directory.add_new_file(path);
     }
+
+    /// Rebuilds the tree from disk, keeping directories opened as they were.
+    pub fn refresh(&mut self) {
+        let mut opened = vec![];
+        self.directory.opened_paths(&mut opened);
+        if let Ok(mut directory) = tree_build(&self.directory.path) {
+            directory.reopen(&opened);
+            self.directory = directory;
+        }
+    }
 }
 
 struct Directory {
@@ -230,6 +240,30 @@ impl Directory {
             }
         }
     }
+
+    /// Collects the paths of this directory and its subdirectories that are open.
+    fn opened_paths(
+        &self,
+        paths: &mut Vec<PathBuf>,
+    ) {
+        if self.is_open {
+            paths.push(self.path.clone());
+        }
+        self.directories
+            .iter()
+            .for_each(|dir| dir.opened_paths(paths));
+    }
+
+    /// Opens this directory and its subdirectories if their path is in `paths`.
+    fn reopen(
+        &mut self,
+        paths: &[PathBuf],
+    ) {
+        self.is_open = paths.contains(&self.path);
+        self.directories
+            .iter_mut()
+            .for_each(|dir| dir.reopen(paths));
+    }
 }
 
 struct File {
@@ -0,0 +1,284 @@
+use crate::data::style::button::{cancel_button, validate_button};
+use crate::data::style::file_tree::{direntry_button, direntry_selected_button};
+use crate::data::style::modal::{font_sample_style, modal_style};
+use crate::file_manager::export::errors::ExportError;
+use crate::file_manager::export::svg::preview_svg;
+use crate::screen::editing;
+use crate::world::fonts::{FontFace, FontFamily};
+use crate::world::TideWorld;
+use iced::advanced::svg::Handle;
+use iced::widget::text_editor::{Action, Edit};
+use iced::widget::{
+    button, center, column, container, opaque, row, scrollable, space, svg, text,
+    text_input, Column,
+};
+use iced::{clipboard, Alignment, Element, Length, Task};
+use std::sync::Arc;
+use typst::syntax::{FileId, Source, VirtualPath};
+
+const PANEL_WIDTH: f32 = 800.0;
+const PANEL_HEIGHT: f32 = 520.0;
+const LIST_WIDTH: f32 = 280.0;
+const SPACING: f32 = 10.0;
+const BUTTON_HEIGHT: f32 = 30.0;
+
+/// Text rendered by Typst to preview the selected font family.
+const SAMPLE_TEXT: &str = "The quick brown fox jumps over the lazy dog. 0123456789";
+
+/// Messages used in the context of the font manager panel.
+#[derive(Debug, Clone)]
+pub enum Message {
+    /// Triggered when the family filter input changes.
+    Filter(String),
+    /// Triggered when the user selects a family in the list.
+    Select(String),
+    /// Result of the rendering of the sample of the given family.
+    SampleRendered(String, Result<Vec<String>, ExportError>),
+    /// Inserts `#set text(font: ...)` for the given family at the cursor.
+    Insert(String),
+    /// Copies the given family name to the clipboard.
+    Copy(String),
+    /// Triggered when the panel is closed.
+    Close,
+}
+
+/// A panel listing the font families known to the Typst world,
+/// with their faces and a sample rendered by Typst.
+pub struct FontManager {
+    /// The panel visibility state.
+    pub visible: bool,
+    /// Families of the font book when the panel was opened.
+    families: Vec<FontFamily>,
+    /// Only the families containing this text (case-insensitive) are listed.
+    filter: String,
+    /// Name of the selected family.
+    selected: Option<String>,
+    /// Sample of the selected family, `None` while it is rendered or if it can't be.
+    sample: Option<Handle>,
+}
+
+impl FontManager {
+    /// Creates a new, hidden, [`FontManager`].
+    pub fn new() -> Self {
+        Self {
+            visible: false,
+            families: vec![],
+            filter: String::new(),
+            selected: None,
+            sample: None,
+        }
+    }
+
+    /// Makes the panel visible, listing the fonts currently known to `world`.
+    pub fn show(
+        &mut self,
+        world: &TideWorld,
+    ) {
+        self.families = world.font_families();
+        self.visible = true;
+    }
+
+    /// Hides the panel and forgets the selection.
+    fn hide(&mut self) {
+        self.visible = false;
+        self.filter = String::new();
+        self.selected = None;
+        self.sample = None;
+    }
+
+    /// Returns the selected family, if any.
+    fn selected_family(&self) -> Option<&FontFamily> {
+        let selected = self.selected.as_ref()?;
+        self.families.iter().find(|family| &family.name == selected)
+    }
+
+    /// Returns the Iced view for the panel.
+    ///
+    /// Lists the (filtered) families on the left and details the selected one on the right.
+    pub fn view(&self) -> Element<'_, Message> {
+        let filter = self.filter.to_lowercase();
+        let list = Column::with_children(
+            self.families
+                .iter()
+                .filter(|family| family.name.to_lowercase().contains(&filter))
+                .map(|family| {
+                    let is_selected = self.selected.as_ref() == Some(&family.name);
+                    button(text(family.name.as_str()))
+                        .on_press(Message::Select(family.name.clone()))
+                        .width(Length::Fill)
+                        .style(if is_selected {
+                            direntry_selected_button
+                        } else {
+                            direntry_button
+                        })
+                        .into()
+                }),
+        );
+        let families = column![
+            text_input("Filter families", &self.filter).on_input(Message::Filter),
+            text(format!("{} families", self.families.len())).size(14),
+            scrollable(list).height(Length::Fill),
+        ]
+        .width(LIST_WIDTH)
+        .spacing(SPACING);
+
+        let details: Element<'_, Message> = match self.selected_family() {
+            Some(family) => view_family(family, self.sample.as_ref()),
+            None => center(text("Select a font family")).into(),
+        };
+
+        let panel = container(
+            column![
+                text("Fonts").size(20),
+                row![families, details]
+                    .spacing(SPACING * 2.0)
+                    .height(Length::Fill),
+                row![
+                    space().width(Length::Fill),
+                    button(text("Close").center())
+                        .on_press(Message::Close)
+                        .height(BUTTON_HEIGHT)
+                        .style(cancel_button),
+                ],
+            ]
+            .spacing(SPACING),
+        )
+        .width(PANEL_WIDTH)
+        .height(PANEL_HEIGHT)
+        .padding(20)
+        .style(modal_style);
+
+        opaque(center(opaque(panel)))
+    }
+
+    /// Handles messages to update the internal state.
+    ///
+    /// Selecting a family renders its sample in the background, using the fonts of `world`.
+    pub fn update(
+        &mut self,
+        message: Message,
+        world: &TideWorld,
+    ) -> Task<editing::Message> {
+        match message {
+            Message::Filter(filter) => {
+                self.filter = filter;
+                Task::none()
+            }
+            Message::Select(family) => {
+                self.selected = Some(family.clone());
+                self.sample = None;
+                Task::perform(preview_svg(sample_world(world, &family)), move |result| {
+                    editing::Message::FontManager(Message::SampleRendered(
+                        family.clone(),
+                        result.map(|sample| sample.output),
+                    ))
+                })
+            }
+            Message::SampleRendered(family, result) => {
+                //the user may have selected another family in the meantime
+                if self.selected.as_ref() == Some(&family) {
+                    match result {
+                        Ok(pages) => {
+                            self.sample = pages
+                                .into_iter()
+                                .next()
+                                .map(|page| Handle::from_memory(page.into_bytes()));
+                        }
+                        Err(err) => {
+                            eprintln!("can't render a sample of {family}: {err:?}")
+                        }
+                    }
+                }
+                Task::none()
+            }
+            Message::Insert(family) => {
+                self.hide();
+                Task::done(editing::Message::ActionPerformed(Action::Edit(
+                    Edit::Paste(Arc::new(set_font_rule(&family))),
+                )))
+            }
+            Message::Copy(family) => clipboard::write(family),
+            Message::Close => {
+                self.hide();
+                Task::none()
+            }
+        }
+    }
+}
+
+/// Returns the view detailing a family: its sample, faces and actions.
+fn view_family<'a>(
+    family: &'a FontFamily,
+    sample: Option<&Handle>,
+) -> Element<'a, Message> {
+    let sample: Element<'a, Message> = match sample {
+        Some(handle) => svg(handle.clone()).width(Length::Fill).into(),
+        None => text("Rendering sample...").into(),
+    };
+
+    column![
+        text(family.name.as_str()).size(24),
+        container(sample)
+            .width(Length::Fill)
+            .padding(SPACING)
+            .style(font_sample_style),
+        scrollable(Column::with_children(family.faces.iter().map(view_face)))
+            .height(Length::Fill),
+        row![
+            button(text("Insert #set text").center())
+                .on_press(Message::Insert(family.name.clone()))
+                .height(BUTTON_HEIGHT)
+                .style(validate_button),
+            button(text("Copy name").center())
+                .on_press(Message::Copy(family.name.clone()))
+                .height(BUTTON_HEIGHT)
+                .style(validate_button),
+        ]
+        .spacing(SPACING),
+    ]
+    .spacing(SPACING)
+    .width(Length::Fill)
+    .into()
+}
+
+/// Returns the line describing a face: variant, coverage and origin.
+fn view_face<'a>(face: &FontFace) -> Element<'a, Message> {
+    let variant = face.variant;
+    row![
+        text(format!(
+            "weight {} · {:?} · stretch {:?}",
+            variant.weight.to_number(),
+            variant.style,
+            variant.stretch.to_ratio()
+        ))
+        .width(Length::Fill),
+        text(format!("{} code points", face.coverage)).size(14),
+        text(face.source.text()).size(14).width(70),
+    ]
+    .spacing(SPACING)
+    .align_y(Alignment::Center)
+    .into()
+}
+
+/// Returns the Typst rule setting the font of the text to `family`.
+fn set_font_rule(family: &str) -> String {
+    //Rust's debug representation of a string is also a valid Typst string
+    format!("#set text(font: {family:?})\n")
+}
+
+/// Returns a copy of `world` whose main file renders a sample of the font `family`.
+fn sample_world(
+    world: &TideWorld,
+    family: &str,
+) -> TideWorld {
+    let id = FileId::new_fake(VirtualPath::new("font-sample.typ"));
+    let text = format!(
+        "#set page(width: auto, height: auto, margin: 4pt, fill: none)\n\
+         #set text(font: {family:?}, size: 16pt)\n\
+         {SAMPLE_TEXT}"
+    );
+    let mut world = world.clone();
+    world.add_source(id, Source::new(id, text));
+    world.change_main(id);
+    world
+}
@@ -1,6 +1,7 @@
 use crate::data::style::button::{cancel_button, toolbar_button, validate_button};
 use crate::data::style::modal;
 use crate::data::style::modal::modal_text_style;
+use crate::file_manager::template::Template;
 use crate::screen::component::toolbar;
 use crate::screen::editing;
 use iced::advanced::text::Shaping;
@@ -150,7 +151,8 @@ pub struct ProjectModal {
     pub warning_text: String,
     /// The modal visibility state.
     pub visible: bool,
-    /// A template file that can be included in the project when it is created.
+    /// A template whose scaffold is copied into the project when it is created,
+    /// see [`Template::load`].
     pub template: Option<PathBuf>,
 }
 
@@ -177,7 +179,7 @@ impl ProjectModal {
         self.visible = true;
     }
 
-    /// Adds the path of a template file to be imported when creating a project.
+    /// Adds the path of a template to start the project from when creating it.
     pub fn require_template(
         &mut self,
         template_path: PathBuf,
@@ -277,13 +279,13 @@ impl ProjectModal {
                                     String::from("Project already exists");
                             } else {
                                 match self.create_project() {
-                                    Ok(path) => {
+                                    Ok((path, main)) => {
                                         if path.exists() {
                                             self.hide();
                                             return Task::done(
                                                 toolbar::Message::OpenProject(
                                                     Some(path),
-                                                    None,
+                                                    main,
                                                 ),
                                             );
                                         } else {
@@ -312,13 +314,16 @@ impl ProjectModal {
         Task::none()
     }
 
-    /// Attempts to create a new project directory with a default `main.typ` file inside.
+    /// Attempts to create a new project directory with a default `main.typ` file inside,
+    /// or with the scaffold of the required template.
+    ///
+    /// Returns the project directory, and the entrypoint of the template to use as main file.
     ///
     /// # Errors
     ///
-    /// Returns an [`io::Error`] if the project path is empty, if the project path already exists
-    /// or if directory/file creation fails.
-    pub fn create_project(&self) -> Result<PathBuf> {
+    /// Returns an [`io::Error`] if the project path is empty, if the project path already exists,
+    /// if the template can't be loaded or if directory/file creation fails.
+    pub fn create_project(&self) -> Result<(PathBuf, Option<PathBuf>)> {
         if self.project_path.is_empty() {
             return Err(io::Error::new(
                 io::ErrorKind::InvalidInput,
@@ -334,17 +339,18 @@ impl ProjectModal {
                 io::ErrorKind::AlreadyExists,
                 "Project path already exists",
             ));
-        } else {
-            std::fs::create_dir_all(&path)?;
-            std::fs::File::create(path.join("main.typ"))?;
-            if let Some(template) = &self.template {
-                let template_path = path.join("template.typ");
-                std::fs::File::create(&template_path)?;
-                std::fs::copy(template, template_path)?;
-            }
-            println!("Project created at: {}", path.display());
         }
 
-        Ok(path)
+        let main = match &self.template {
+            Some(template) => Some(Template::load(template)?.create_project(&path)?),
+            None => {
+                std::fs::create_dir_all(&path)?;
+                std::fs::File::create(path.join("main.typ"))?;
+                None
+            }
+        };
+        println!("Project created at: {}", path.display());
+
+        Ok((path, main))
     }
 }
@@ -8,7 +8,7 @@ use typst::syntax::FileId;
 use crate::data::style::button::{cancel_button, validate_button};
 use crate::data::style::pop_up::{confirm, darker_bg, error, title_text, warning};
 
-/// Represents a pop-up dialog that can display a warning, error, or confirmation message to the user.
+/// Represents a pop-up dialog that can display an information, warning, error, or confirmation message to the user.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct PopUpElement {
     /// The type of pop-up (see [`PopUpType`]).
@@ -60,7 +60,10 @@ impl PopUpElement {
             .push(text(self.details()).size(20))
             .spacing(20);
         let row = match self.pop_type {
-            PopUpType::Confirm(id) => button_row(Message::DeleteFile(id)),
+            PopUpType::Confirm(id) => button_row("Cancel", "Ok", Message::DeleteFile(id)),
+            PopUpType::Conflict(id) => {
+                button_row("Keep mine", "Reload", Message::ReloadFile(id))
+            }
             _ => Row::new()
                 .push(space().width(500))
                 .push(
@@ -79,7 +82,9 @@ impl PopUpElement {
                     match self.pop_type {
                         PopUpType::Warning => warning,
                         PopUpType::Error => error,
-                        PopUpType::Confirm(_) => confirm,
+                        PopUpType::Info
+                        | PopUpType::Confirm(_)
+                        | PopUpType::Conflict(_) => confirm,
                     },
                 ),
             ))
@@ -91,21 +96,28 @@ impl PopUpElement {
 /// Represents the different types of pop-up dialogs.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum PopUpType {
+    /// An information message.
+    Info,
     /// A warning message.
     Warning,
     /// An error message.
     Error,
     /// A confirmation dialog tied to a file identified by its [`FileId`].
     Confirm(FileId),
+    /// A conflict between the buffer of a file identified by its [`FileId`]
+    /// and its content changed on disk.
+    Conflict(FileId),
 }
 
 impl PopUpType {
-    /// Returns a string representation of the pop-up type (e.g., "WARNING", "ERROR", "CONFIRM").
+    /// Returns a string representation of the pop-up type (e.g., "INFO", "WARNING", "ERROR", "CONFIRM").
     fn text(&self) -> String {
         match self {
+            Self::Info => "INFO",
             Self::Warning => "WARNING",
             Self::Error => "ERROR",
             Self::Confirm(_) => "CONFIRM",
+            Self::Conflict(_) => "CONFLICT",
         }
         .to_owned()
     }
@@ -120,19 +132,26 @@ pub enum Message {
     HidePopUp,
     /// Triggered when the user confirms deletion of a file.
     DeleteFile(FileId),
+    /// Triggered when the user chooses to reload a file from disk, discarding its buffer.
+    ReloadFile(FileId),
 }
 
-/// Returns a [`Row`] widget containing "Cancel" and "Ok" buttons for confirmation pop-ups.
+/// Returns a [`Row`] widget containing a cancel and a validate button for confirmation pop-ups.
 ///
-/// `on_press` is the message that will be sent when "Ok" is pressed.
-fn button_row<'a>(on_press: Message) -> Row<'a, Message> {
+/// `cancel` and `validate` are the button labels,
+/// `on_press` is the message that will be sent when the validate button is pressed.
+fn button_row<'a>(
+    cancel: &'a str,
+    validate: &'a str,
+    on_press: Message,
+) -> Row<'a, Message> {
     row![
         space().width(500),
-        button(Text::new("Cancel").shaping(Shaping::Advanced))
+        button(Text::new(cancel).shaping(Shaping::Advanced))
             .on_press(Message::HidePopUp)
             .style(cancel_button),
         space().width(20),
-        button(Text::new("Ok").shaping(Shaping::Advanced))
+        button(Text::new(validate).shaping(Shaping::Advanced))
             .on_press(on_press)
             .style(validate_button)
     ]
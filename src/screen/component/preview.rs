@@ -1,21 +1,795 @@
-use iced::advanced::svg::Handle;
+use crate::data::config::project::{PreviewConfig, Zoom};
+use crate::data::style::button::simple_button;
+use crate::data::style::preview::{
+    cursor_marker_style, inverted_page_placeholder_style, page_placeholder_style,
+};
+use crate::file_manager::export::preview::{page_hash, render_page};
+use crate::world::TideWorld;
+use iced::keyboard::{self, Key, Modifiers};
+use iced::mouse::ScrollDelta;
+use iced::widget::image::Handle;
+use iced::widget::operation::{self, AbsoluteOffset};
+use iced::widget::scrollable::{Direction, Scrollbar, Viewport};
+use iced::widget::{
+    button, column, container, image, mouse_area, row, scrollable, space, stack, text,
+    Column,
+};
+use iced::{
+    event, Alignment, Element, Event, Length, Padding, Point, Size, Subscription, Task,
+};
+use std::collections::HashMap;
+use std::ops::Range;
+use std::time::Duration;
+use typst::layout::{Abs, PagedDocument, Point as PagePoint, Position};
+use typst::syntax::Source;
+use typst_ide::Jump;
 
-/// Represents the current state of the document preview, including rendering handles and display mode.
-pub struct Preview {
-    /// A collection of rendering handles associated with the preview.
+/// Space around and between the pages, in logical pixels.
+const PAGE_SPACING: f32 = 15.0;
+/// Width reserved for the scrollbar, in logical pixels.
+const SCROLLBAR_WIDTH: f32 = 10.0;
+/// Width of the preview until its actual width is known, in logical pixels.
+const DEFAULT_WIDTH: f32 = 600.0;
+/// Height of the preview until its actual height is known, in logical pixels.
+const DEFAULT_HEIGHT: f32 = 800.0;
+/// Pages within this many viewport heights of the viewport are rendered ahead of scrolling.
+const RENDER_MARGIN: f32 = 1.0;
+/// Bitmaps of the pages beyond this many viewport heights of the viewport are dropped.
+const KEEP_MARGIN: f32 = 3.0;
+/// Pixels rendered per displayed logical pixel, for sharp pages on high density screens.
+const QUALITY: f32 = 2.0;
+/// Pixels rendered per displayed logical pixel while the preview is resized.
+const DRAFT_QUALITY: f32 = 0.75;
+/// Delay without resizing after which the pages are rendered at full quality.
+const IDLE_DELAY: Duration = Duration::from_millis(300);
+/// Relative difference of resolution under which a bitmap is not rendered again.
+const SCALE_TOLERANCE: f32 = 0.01;
+/// Logical pixels per point at 100% zoom, the size of a page printed at 96 DPI.
+const PIXELS_PER_POINT: f32 = 96.0 / 72.0;
+/// Zoom levels, in percent, stepped through when zooming in and out.
+const ZOOM_STEPS: [u16; 14] = [
+    25, 33, 50, 67, 75, 90, 100, 110, 125, 150, 200, 300, 400, 500,
+];
+/// Zoom factor of one line scrolled with `Ctrl` held.
+const SCROLL_ZOOM_FACTOR: f32 = 1.1;
+/// Pixels of a precise scroll (e.g. on a touchpad) counted as one line.
+const PIXELS_PER_LINE: f32 = 50.0;
+/// Id of the scrollable of the preview, to keep the displayed page in place when zooming.
+const SCROLLABLE_ID: &str = "preview";
+/// Duration of the highlight marking the position of the editor cursor.
+const MARKER_DURATION: Duration = Duration::from_millis(800);
+/// Height of the highlight above the baseline of the marked text, in points.
+const MARKER_ASCENT: f32 = 11.0;
+/// Height of the highlight, in points.
+const MARKER_HEIGHT: f32 = 14.0;
+
+/// Messages used in the context of the preview.
+#[derive(Debug, Clone)]
+pub enum Message {
+    /// A page with the given hash was rendered with the given number of pixels per point,
+    /// with inverted colors or not.
+    Rendered(u128, f32, bool, Handle),
+    /// The preview was scrolled or resized.
+    Viewport(Viewport),
+    /// The preview was not resized since the interaction with the given number.
+    Idle(u64),
+    /// Zooms in to the next zoom step.
+    ZoomIn,
+    /// Zooms out to the previous zoom step.
+    ZoomOut,
+    /// Resets the zoom so that the pages fill the width of the preview.
+    ResetZoom,
+    /// Sets the zoom of the preview.
+    SetZoom(Zoom),
+    /// The preview was scrolled with `Ctrl` held.
+    ScrollZoom(ScrollDelta),
+    /// The keyboard modifiers changed.
+    ModifiersChanged(Modifiers),
+    /// The mouse moved over the page with the given index, to the given position
+    /// relative to the page, in logical pixels.
+    CursorMoved(usize, Point),
+    /// The page under the mouse was pressed.
+    PagePressed,
+    /// The page with the given index was clicked at the given position.
     ///
-    /// This may be `None` if no preview is currently loaded or available.
-    pub handle: Option<Vec<Handle>>,
+    /// Handled by the editing screen, see [`Preview::jump_from_click`].
+    Clicked(usize, PagePoint),
+    /// The highlight with the given number is over.
+    HideMarker(u64),
+}
+
+/// A page of the previewed document.
+#[derive(Debug, Clone)]
+struct PreviewPage {
+    /// Hash of the page content, see [`page_hash`].
+    hash: u128,
+    /// Size of the page, in points.
+    size: Size,
+    /// Bitmap of the page with its number of pixels per point, `None` until rendered.
+    image: Option<(Handle, f32)>,
+}
+
+/// Represents the current state of the document preview.
+///
+/// The pages are rendered as bitmaps in background tasks, at the resolution of the
+/// displayed width. Pages whose content did not change keep their bitmap.
+///
+/// Only the pages in or near the viewport are rendered and displayed, so that the cost of
+/// the preview does not depend on the length of the document.
+///
+/// The pages are sized according to the [`Zoom`], stored in the project configuration.
+pub struct Preview {
+    /// The previewed document, `None` if no preview is currently loaded.
+    document: Option<PagedDocument>,
+    /// The pages of the document with their bitmaps.
+    pages: Vec<PreviewPage>,
+    /// Width of the preview area, in logical pixels.
+    width: f32,
+    /// Height of the preview area, in logical pixels.
+    height: f32,
+    /// Vertical scroll offset of the preview, in logical pixels.
+    offset: f32,
+    /// Zoom of the pages.
+    zoom: Zoom,
+    /// Keyboard modifiers currently held, `Ctrl` turning scrolling into zooming.
+    modifiers: Modifiers,
+    /// Index of the page under the mouse, with the position of the mouse on the page.
+    hovered: Option<(usize, Point)>,
+    /// Position of the editor cursor highlighted in the document, with its number.
+    marker: Option<(u64, Position)>,
+    /// Number of the last highlight.
+    markers: u64,
+    /// Number of the last resize of the preview.
+    interaction: u64,
+    /// Number of pixels per point of the bitmaps being rendered, by page hash.
+    pending: HashMap<u128, f32>,
     /// Indicates whether the preview should be displayed in inverted (e.g., dark mode) colors.
-    pub is_inverted: bool,
+    is_inverted: bool,
 }
 
 impl Preview {
-    /// Creates a new [`Preview`] instance with no handle and non-inverted colors.
-    pub fn new() -> Self {
+    /// Creates a new [`Preview`] instance with no document, displayed as set in `config`.
+    pub fn new(config: &PreviewConfig) -> Self {
         Self {
-            handle: None,
-            is_inverted: false,
+            document: None,
+            pages: vec![],
+            width: DEFAULT_WIDTH,
+            height: DEFAULT_HEIGHT,
+            offset: 0.0,
+            zoom: config.zoom,
+            modifiers: Modifiers::default(),
+            hovered: None,
+            marker: None,
+            markers: 0,
+            interaction: 0,
+            pending: HashMap::new(),
+            is_inverted: config.inverted,
+        }
+    }
+
+    /// Returns `true` if the preview is displayed in inverted colors.
+    pub fn is_inverted(&self) -> bool {
+        self.is_inverted
+    }
+
+    /// Displays the preview in inverted colors or not.
+    ///
+    /// Returns the task rendering the pages again.
+    pub fn set_inverted(
+        &mut self,
+        inverted: bool,
+    ) -> Task<Message> {
+        if inverted == self.is_inverted {
+            return Task::none();
+        }
+        self.is_inverted = inverted;
+        for page in &mut self.pages {
+            page.image = None;
+        }
+        self.pending.clear();
+        self.render_pages(QUALITY)
+    }
+
+    /// Returns `true` if a document is previewed.
+    pub fn is_loaded(&self) -> bool {
+        self.document.is_some()
+    }
+
+    /// Previews `document`, reusing the bitmaps of the pages which did not change.
+    ///
+    /// Returns the task rendering the other pages.
+    pub fn set_document(
+        &mut self,
+        document: PagedDocument,
+    ) -> Task<Message> {
+        let images: HashMap<u128, (Handle, f32)> = self
+            .pages
+            .drain(..)
+            .filter_map(|page| Some((page.hash, page.image?)))
+            .collect();
+        self.pages = document
+            .pages
+            .iter()
+            .map(|page| {
+                let hash = page_hash(page);
+                let size = page.frame.size();
+                PreviewPage {
+                    hash,
+                    size: Size::new(size.x.to_pt() as f32, size.y.to_pt() as f32),
+                    image: images.get(&hash).cloned(),
+                }
+            })
+            .collect();
+        self.document = Some(document);
+        self.render_pages(QUALITY)
+    }
+
+    /// Returns the zoom of the preview.
+    pub fn zoom(&self) -> Zoom {
+        self.zoom
+    }
+
+    /// Sets the zoom of the preview, keeping the page at the top of the viewport in place.
+    ///
+    /// Returns the task scrolling to that page and rendering the pages at the new size.
+    pub fn set_zoom(
+        &mut self,
+        zoom: Zoom,
+    ) -> Task<Message> {
+        if zoom == self.zoom {
+            return Task::none();
+        }
+        let (tops, _) = self.layout();
+        let anchor = self.pages_near_viewport(0.0).start;
+        let fraction = match self.pages.get(anchor) {
+            Some(page) => {
+                ((self.offset - tops[anchor]) / self.page_height(page)).max(0.0)
+            }
+            None => 0.0,
+        };
+        self.zoom = zoom;
+        let (tops, _) = self.layout();
+        if let Some(page) = self.pages.get(anchor) {
+            self.offset = tops[anchor] + fraction * self.page_height(page);
+        }
+        let scroll = operation::scroll_to(
+            SCROLLABLE_ID,
+            AbsoluteOffset {
+                x: None,
+                y: Some(self.offset),
+            },
+        );
+        Task::batch([scroll, self.resize()])
+    }
+
+    /// Returns where to jump to after a click at `click` on the page with index `page`:
+    /// the source of the clicked element, or the destination of the clicked link.
+    pub fn jump_from_click(
+        &self,
+        world: &TideWorld,
+        page: usize,
+        click: PagePoint,
+    ) -> Option<Jump> {
+        let document = self.document.as_ref()?;
+        let frame = &document.pages.get(page)?.frame;
+        typst_ide::jump_from_click(world, document, frame, click)
+    }
+
+    /// Returns the task scrolling the preview to `position` in the document,
+    /// displayed at the top of the viewport.
+    pub fn scroll_to_position(
+        &mut self,
+        position: &Position,
+    ) -> Task<Message> {
+        let index = position.page.get() - 1;
+        let (tops, _) = self.layout();
+        let Some(page) = self.pages.get(index) else {
+            return Task::none();
+        };
+        let y = position.point.y.to_pt() as f32 * self.page_scale(page);
+        self.scroll_to(tops[index] + y - PAGE_SPACING)
+    }
+
+    /// Returns the task scrolling the preview to the vertical `offset`.
+    fn scroll_to(
+        &mut self,
+        offset: f32,
+    ) -> Task<Message> {
+        self.offset = offset.max(0.0);
+        Task::batch([
+            operation::scroll_to(
+                SCROLLABLE_ID,
+                AbsoluteOffset {
+                    x: None,
+                    y: Some(self.offset),
+                },
+            ),
+            self.render_pages(QUALITY),
+        ])
+    }
+
+    /// Briefly highlights the output of the text at the byte offset `cursor` of `source`,
+    /// scrolling the preview to it if it is out of the viewport.
+    ///
+    /// If the text appears several times in the document, the closest to the viewport
+    /// is highlighted.
+    pub fn reveal_cursor(
+        &mut self,
+        source: &Source,
+        cursor: usize,
+    ) -> Task<Message> {
+        let Some(document) = &self.document else {
+            return Task::none();
+        };
+        let (tops, _) = self.layout();
+        let center = self.offset + self.height / 2.0;
+        let closest = typst_ide::jump_from_cursor(document, source, cursor)
+            .into_iter()
+            .filter_map(|position| {
+                let index = position.page.get() - 1;
+                let page = self.pages.get(index)?;
+                let y =
+                    tops[index] + position.point.y.to_pt() as f32 * self.page_scale(page);
+                Some((y, position))
+            })
+            .min_by(|(a, _), (b, _)| (a - center).abs().total_cmp(&(b - center).abs()));
+        let Some((y, position)) = closest else {
+            return Task::none();
+        };
+
+        self.markers += 1;
+        let marker = self.markers;
+        self.marker = Some((marker, position));
+        let hide = Task::perform(tokio::time::sleep(MARKER_DURATION), move |_| {
+            Message::HideMarker(marker)
+        });
+        if y < self.offset || y > self.offset + self.height {
+            Task::batch([self.scroll_to(y - self.height / 3.0), hide])
+        } else {
+            hide
+        }
+    }
+
+    /// Returns the displayed zoom of the page at the top of the viewport, in percent.
+    fn zoom_percent(&self) -> u16 {
+        if let Zoom::Percent(percent) = self.zoom {
+            return percent;
+        }
+        match self.pages.get(self.pages_near_viewport(0.0).start) {
+            Some(page) => {
+                (self.page_scale(page) / PIXELS_PER_POINT * 100.0).round() as u16
+            }
+            None => 100,
+        }
+    }
+
+    /// Returns the zoom after scrolling `delta` with `Ctrl` held.
+    fn scrolled_zoom(
+        &self,
+        delta: ScrollDelta,
+    ) -> Zoom {
+        let lines = match delta {
+            ScrollDelta::Lines { y, .. } => y,
+            ScrollDelta::Pixels { y, .. } => y / PIXELS_PER_LINE,
+        };
+        let current = self.zoom_percent();
+        let mut percent =
+            (current as f32 * SCROLL_ZOOM_FACTOR.powf(lines)).round() as u16;
+        //small precise scrolls still change the zoom
+        if percent == current && lines > 0.0 {
+            percent += 1;
+        } else if percent == current && lines < 0.0 {
+            percent = percent.saturating_sub(1);
         }
+        Zoom::Percent(percent.clamp(ZOOM_STEPS[0], ZOOM_STEPS[ZOOM_STEPS.len() - 1]))
+    }
+
+    /// Returns the width available to the pages, in logical pixels.
+    fn available_width(&self) -> f32 {
+        (self.width - 2.0 * PAGE_SPACING - SCROLLBAR_WIDTH).max(1.0)
+    }
+
+    /// Returns the height available to a page, in logical pixels.
+    fn available_height(&self) -> f32 {
+        (self.height - 2.0 * PAGE_SPACING - SCROLLBAR_WIDTH).max(1.0)
+    }
+
+    /// Returns the number of logical pixels per point of the displayed `page`.
+    fn page_scale(
+        &self,
+        page: &PreviewPage,
+    ) -> f32 {
+        let fit_width = self.available_width() / page.size.width.max(1.0);
+        match self.zoom {
+            Zoom::FitWidth => fit_width,
+            Zoom::FitPage => {
+                fit_width.min(self.available_height() / page.size.height.max(1.0))
+            }
+            Zoom::Percent(percent) => percent as f32 / 100.0 * PIXELS_PER_POINT,
+        }
+    }
+
+    /// Returns the displayed width of `page`, in logical pixels.
+    fn page_width(
+        &self,
+        page: &PreviewPage,
+    ) -> f32 {
+        page.size.width * self.page_scale(page)
+    }
+
+    /// Returns the displayed height of `page`, in logical pixels.
+    fn page_height(
+        &self,
+        page: &PreviewPage,
+    ) -> f32 {
+        page.size.height * self.page_scale(page)
+    }
+
+    /// Returns the vertical position of the top of each page in the preview,
+    /// and the height of the whole preview, in logical pixels.
+    fn layout(&self) -> (Vec<f32>, f32) {
+        let mut top = PAGE_SPACING;
+        let tops = self
+            .pages
+            .iter()
+            .map(|page| {
+                let page_top = top;
+                top += self.page_height(page) + PAGE_SPACING;
+                page_top
+            })
+            .collect();
+        (tops, top)
+    }
+
+    /// Returns the range of the pages within `margin` viewport heights of the viewport.
+    fn pages_near_viewport(
+        &self,
+        margin: f32,
+    ) -> Range<usize> {
+        let start = self.offset - margin * self.height;
+        let end = self.offset + (1.0 + margin) * self.height;
+        let (tops, _) = self.layout();
+        let first = tops
+            .iter()
+            .zip(&self.pages)
+            .position(|(top, page)| top + self.page_height(page) >= start)
+            .unwrap_or(self.pages.len());
+        let last = tops
+            .iter()
+            .position(|top| *top > end)
+            .unwrap_or(self.pages.len());
+        first..last.max(first)
+    }
+
+    /// Drops the bitmaps of the pages far from the viewport, to bound the memory used.
+    fn drop_hidden_images(&mut self) {
+        let kept = self.pages_near_viewport(KEEP_MARGIN);
+        for (index, page) in self.pages.iter_mut().enumerate() {
+            if !kept.contains(&index) {
+                page.image = None;
+            }
+        }
+    }
+
+    /// Returns the number of pixels per point of a bitmap of `page` rendered with `quality`
+    /// pixels per displayed logical pixel.
+    fn scale(
+        &self,
+        page: &PreviewPage,
+        quality: f32,
+    ) -> f32 {
+        self.page_scale(page) * quality
+    }
+
+    /// Returns the task rendering, with `quality` pixels per displayed logical pixel,
+    /// the pages near the viewport without bitmap or whose bitmap has another resolution.
+    ///
+    /// Drafts (below [`QUALITY`]) are only rendered for pages whose bitmap is coarser,
+    /// and pages with the same content are rendered once.
+    fn render_pages(
+        &mut self,
+        quality: f32,
+    ) -> Task<Message> {
+        let Some(document) = &self.document else {
+            return Task::none();
+        };
+        let near = self.pages_near_viewport(RENDER_MARGIN);
+        let mut tasks = vec![];
+        for (page, preview) in document.pages[near.clone()].iter().zip(&self.pages[near])
+        {
+            let scale = self.scale(preview, quality);
+            let outdated = match &preview.image {
+                None => true,
+                Some((_, current)) if quality < QUALITY => *current < scale,
+                Some((_, current)) => (current - scale).abs() > scale * SCALE_TOLERANCE,
+            };
+            let pending = self.pending.get(&preview.hash).is_some_and(|pending| {
+                (pending - scale).abs() <= scale * SCALE_TOLERANCE
+            });
+            if !outdated || pending {
+                continue;
+            }
+            self.pending.insert(preview.hash, scale);
+            let hash = preview.hash;
+            let inverted = self.is_inverted;
+            tasks.push(Task::perform(
+                render_page(page.clone(), scale, inverted),
+                move |raster| {
+                    let handle =
+                        Handle::from_rgba(raster.width, raster.height, raster.pixels);
+                    Message::Rendered(hash, scale, inverted, handle)
+                },
+            ));
+        }
+        Task::batch(tasks)
+    }
+
+    /// Handles messages to update the internal state.
+    pub fn update(
+        &mut self,
+        message: Message,
+    ) -> Task<Message> {
+        match message {
+            Message::Rendered(hash, scale, inverted, handle) => {
+                //the colors changed since the render started
+                if inverted != self.is_inverted {
+                    return Task::none();
+                }
+                if self.pending.get(&hash) == Some(&scale) {
+                    self.pending.remove(&hash);
+                }
+                let kept = self.pages_near_viewport(KEEP_MARGIN);
+                let targets: Vec<f32> = self.pages[kept.clone()]
+                    .iter()
+                    .map(|page| self.scale(page, QUALITY))
+                    .collect();
+                for (page, target) in self.pages[kept].iter_mut().zip(targets) {
+                    if page.hash != hash {
+                        continue;
+                    }
+                    //a late draft must not replace a sharper bitmap
+                    let is_better = page.image.as_ref().is_none_or(|(_, current)| {
+                        (scale - target).abs() < (current - target).abs()
+                    });
+                    if is_better {
+                        page.image = Some((handle.clone(), scale));
+                    }
+                }
+                Task::none()
+            }
+            Message::Viewport(viewport) => {
+                self.offset = viewport.absolute_offset().y;
+                self.drop_hidden_images();
+                let bounds = viewport.bounds();
+                if (bounds.width - self.width).abs() < 1.0
+                    && (bounds.height - self.height).abs() < 1.0
+                {
+                    return self.render_pages(QUALITY);
+                }
+                self.width = bounds.width;
+                self.height = bounds.height;
+                self.resize()
+            }
+            Message::Idle(interaction) => {
+                if interaction != self.interaction {
+                    return Task::none();
+                }
+                self.render_pages(QUALITY)
+            }
+            Message::ZoomIn => {
+                let current = self.zoom_percent();
+                let next = ZOOM_STEPS.iter().find(|step| **step > current);
+                self.set_zoom(Zoom::Percent(
+                    *next.unwrap_or(&ZOOM_STEPS[ZOOM_STEPS.len() - 1]),
+                ))
+            }
+            Message::ZoomOut => {
+                let current = self.zoom_percent();
+                let previous = ZOOM_STEPS.iter().rev().find(|step| **step < current);
+                self.set_zoom(Zoom::Percent(*previous.unwrap_or(&ZOOM_STEPS[0])))
+            }
+            Message::ResetZoom => self.set_zoom(Zoom::default()),
+            Message::SetZoom(zoom) => self.set_zoom(zoom),
+            Message::ScrollZoom(delta) => self.set_zoom(self.scrolled_zoom(delta)),
+            Message::ModifiersChanged(modifiers) => {
+                self.modifiers = modifiers;
+                Task::none()
+            }
+            Message::CursorMoved(index, position) => {
+                self.hovered = Some((index, position));
+                Task::none()
+            }
+            Message::PagePressed => {
+                let Some((index, position)) = self.hovered else {
+                    return Task::none();
+                };
+                let Some(page) = self.pages.get(index) else {
+                    return Task::none();
+                };
+                let scale = self.page_scale(page) as f64;
+                let click = PagePoint::new(
+                    Abs::pt(position.x as f64 / scale),
+                    Abs::pt(position.y as f64 / scale),
+                );
+                Task::done(Message::Clicked(index, click))
+            }
+            Message::Clicked(..) => Task::none(),
+            Message::HideMarker(marker) => {
+                if self
+                    .marker
+                    .as_ref()
+                    .is_some_and(|(current, _)| *current == marker)
+                {
+                    self.marker = None;
+                }
+                Task::none()
+            }
+        }
+    }
+
+    /// Returns the task rendering drafts of the pages after their size changed,
+    /// then rendering them at full quality once the size stops changing.
+    fn resize(&mut self) -> Task<Message> {
+        self.drop_hidden_images();
+        self.interaction += 1;
+        let interaction = self.interaction;
+        Task::batch([
+            self.render_pages(DRAFT_QUALITY),
+            Task::perform(tokio::time::sleep(IDLE_DELAY), move |_| {
+                Message::Idle(interaction)
+            }),
+        ])
+    }
+
+    /// Returns the subscription to the zoom shortcuts pressed outside of the editor
+    /// (see [`bindings`](crate::editor::bindings::bindings)) and to the keyboard modifiers.
+    pub fn subscription(&self) -> Subscription<Message> {
+        event::listen_with(|event, status, _window| match event {
+            Event::Keyboard(keyboard::Event::ModifiersChanged(modifiers)) => {
+                Some(Message::ModifiersChanged(modifiers))
+            }
+            Event::Keyboard(keyboard::Event::KeyPressed { key, modifiers, .. })
+                if modifiers.command() && status == event::Status::Ignored =>
+            {
+                zoom_shortcut(&key)
+            }
+            _ => None,
+        })
+    }
+
+    /// Returns the Iced view for the preview: the zoom controls above the pages,
+    /// displayed one below the other.
+    ///
+    /// Only the pages near the viewport are displayed, the others are replaced by spaces of
+    /// the same height. Pages not rendered yet are shown as blank pages of the same size.
+    pub fn view(&self) -> Element<'_, Message> {
+        let indicator = match self.zoom {
+            Zoom::FitWidth => format!("{}% (fit width)", self.zoom_percent()),
+            Zoom::FitPage => format!("{}% (fit page)", self.zoom_percent()),
+            Zoom::Percent(percent) => format!("{percent}%"),
+        };
+        let controls = row![
+            button(text("-"))
+                .on_press(Message::ZoomOut)
+                .style(simple_button),
+            text(indicator).size(12),
+            button(text("+"))
+                .on_press(Message::ZoomIn)
+                .style(simple_button),
+            space().width(Length::Fill),
+            button(text("Fit width").size(12))
+                .on_press(Message::SetZoom(Zoom::FitWidth))
+                .style(simple_button),
+            button(text("Fit page").size(12))
+                .on_press(Message::SetZoom(Zoom::FitPage))
+                .style(simple_button),
+        ]
+        .spacing(5)
+        .padding([2.0, PAGE_SPACING])
+        .align_y(Alignment::Center);
+
+        let (tops, total) = self.layout();
+        let near = self.pages_near_viewport(RENDER_MARGIN);
+        let before = tops.get(near.start).copied().unwrap_or(total);
+        let after = tops.get(near.end).copied().unwrap_or(total);
+
+        let widest = self.pages[near.clone()]
+            .iter()
+            .map(|page| self.page_width(page))
+            .fold(0.0, f32::max);
+        let content_width =
+            (widest + 2.0 * PAGE_SPACING).max(self.width - SCROLLBAR_WIDTH);
+
+        let mut pages = Column::new()
+            .width(content_width)
+            .align_x(Alignment::Center)
+            .push(space().height(before));
+        for (index, page) in self
+            .pages
+            .iter()
+            .enumerate()
+            .take(near.end)
+            .skip(near.start)
+        {
+            let width = self.page_width(page);
+            let height = self.page_height(page);
+            let content = match &page.image {
+                Some((handle, _)) => {
+                    Element::from(image(handle.clone()).width(width).height(height))
+                }
+                None => container(space())
+                    .width(width)
+                    .height(height)
+                    .style(if self.is_inverted {
+                        inverted_page_placeholder_style
+                    } else {
+                        page_placeholder_style
+                    })
+                    .into(),
+            };
+            let content = match &self.marker {
+                Some((_, position)) if position.page.get() == index + 1 => {
+                    let scale = self.page_scale(page);
+                    let top = (position.point.y.to_pt() as f32 - MARKER_ASCENT) * scale;
+                    let marker = column![
+                        space().height(top.max(0.0)),
+                        container(space())
+                            .width(Length::Fill)
+                            .height(MARKER_HEIGHT * scale)
+                            .style(cursor_marker_style),
+                    ]
+                    .width(width)
+                    .height(height);
+                    stack![content, marker].into()
+                }
+                _ => content,
+            };
+            //clicking a page jumps to the source of the clicked element
+            pages = pages.push(
+                mouse_area(content)
+                    .on_move(move |position| Message::CursorMoved(index, position))
+                    .on_press(Message::PagePressed),
+            );
+            pages = pages.push(space().height(PAGE_SPACING));
+        }
+        pages = pages.push(space().height(total - after));
+
+        let pages = pages.padding(Padding {
+            left: PAGE_SPACING,
+            right: PAGE_SPACING,
+            ..Padding::ZERO
+        });
+        //scrolling zooms while Ctrl is held
+        let pages = if self.modifiers.command() {
+            mouse_area(pages).on_scroll(Message::ScrollZoom)
+        } else {
+            mouse_area(pages)
+        };
+
+        let pages = scrollable(pages)
+            .id(SCROLLABLE_ID)
+            .direction(Direction::Both {
+                vertical: Scrollbar::default(),
+                horizontal: Scrollbar::default(),
+            })
+            .on_scroll(Message::Viewport)
+            .width(Length::Fill)
+            .height(Length::Fill);
+
+        column![controls, pages].into()
+    }
+}
+
+/// Returns the zoom message of the key pressed with `Ctrl`, if any.
+///
+/// - `Ctrl + =` or `Ctrl + +` --> Zoom in
+/// - `Ctrl + -` --> Zoom out
+/// - `Ctrl + 0` --> Reset the zoom to fit the width of the preview
+pub fn zoom_shortcut(key: &Key) -> Option<Message> {
+    match key.as_ref() {
+        Key::Character("=") | Key::Character("+") => Some(Message::ZoomIn),
+        Key::Character("-") => Some(Message::ZoomOut),
+        Key::Character("0") => Some(Message::ResetZoom),
+        _ => None,
     }
 }
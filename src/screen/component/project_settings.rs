@@ -0,0 +1,281 @@
+use crate::data::config::project::{ProjectConfig, DATE_FORMAT};
+use crate::data::style::button::{cancel_button, simple_button, validate_button};
+use crate::data::style::modal::{modal_style, modal_text_style};
+use crate::icon;
+use crate::screen::editing;
+use iced::widget::{
+    button, center, column, container, opaque, row, scrollable, space, text, text_input,
+    Column,
+};
+use chrono::NaiveDate;
+use iced::{Alignment, Element, Length, Task};
+use typst::text::Lang;
+
+const MODAL_WIDTH: f32 = 500.0;
+const MODAL_HEIGHT: f32 = 540.0;
+const MODAL_BUTTON_HEIGHT: f32 = 30.0;
+const MODAL_BUTTON_WIDTH: f32 = 80.0;
+const SPACING: f32 = 10.0;
+
+/// Messages used in the context of the project settings modal.
+#[derive(Debug, Clone)]
+pub enum Message {
+    /// Triggered when the key of the input at the given index changes.
+    InputKey(usize, String),
+    /// Triggered when the value of the input at the given index changes.
+    InputValue(usize, String),
+    /// Adds an empty input.
+    AddInput,
+    /// Removes the input at the given index.
+    RemoveInput(usize),
+    /// Triggered when the default font input changes.
+    Font(String),
+    /// Triggered when the default text size input changes.
+    Size(String),
+    /// Triggered when the default language input changes.
+    Lang(String),
+    /// Triggered when the fixed date input changes.
+    Date(String),
+    /// Triggered when the user applies the settings.
+    Apply,
+    /// Triggered when the modal is canceled.
+    Cancel,
+}
+
+/// A modal dialog used to edit the `sys.inputs`, the default styles
+/// and the fixed date of the project.
+pub struct ProjectSettings {
+    /// The modal visibility state.
+    pub visible: bool,
+    /// Configuration of the project when the modal was opened.
+    config: ProjectConfig,
+    /// Key-value pairs exposed through `sys.inputs`, in edition.
+    inputs: Vec<(String, String)>,
+    /// Default font family, empty for Typst's default.
+    font: String,
+    /// Default text size in points, empty for Typst's default.
+    size: String,
+    /// Default language, empty for Typst's default.
+    lang: String,
+    /// Fixed date of the documents, empty to use the current date.
+    date: String,
+    /// Warning text to show in case of invalid input.
+    warning_text: String,
+}
+
+impl ProjectSettings {
+    /// Creates a new, hidden, [`ProjectSettings`] modal.
+    pub fn new() -> Self {
+        Self {
+            visible: false,
+            config: ProjectConfig::default(),
+            inputs: vec![],
+            font: String::new(),
+            size: String::new(),
+            lang: String::new(),
+            date: String::new(),
+            warning_text: String::new(),
+        }
+    }
+
+    /// Makes the modal visible, filled with the given project configuration.
+    pub fn show(
+        &mut self,
+        config: &ProjectConfig,
+    ) {
+        self.config = config.clone();
+        self.inputs = config
+            .inputs
+            .iter()
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect();
+        self.font = config.style.font.clone().unwrap_or_default();
+        self.size = config
+            .style
+            .size
+            .map(|size| size.to_string())
+            .unwrap_or_default();
+        self.lang = config.style.lang.clone().unwrap_or_default();
+        self.date = config.build.date.clone().unwrap_or_default();
+        self.warning_text = String::new();
+        self.visible = true;
+    }
+
+    /// Makes the modal invisible.
+    fn hide(&mut self) {
+        self.visible = false;
+    }
+
+    /// Builds the project configuration from the fields of the modal.
+    ///
+    /// # Errors
+    ///
+    /// Returns a warning text if a field is invalid.
+    fn to_config(&self) -> Result<ProjectConfig, String> {
+        let mut config = self.config.clone();
+
+        config.inputs.clear();
+        for (key, value) in &self.inputs {
+            let key = key.trim();
+            if key.is_empty() {
+                return Err(String::from("Input with no name"));
+            }
+            if config
+                .inputs
+                .insert(key.to_owned(), value.clone())
+                .is_some()
+            {
+                return Err(format!("Input \"{key}\" defined twice"));
+            }
+        }
+
+        let optional = |field: &str| {
+            let field = field.trim();
+            (!field.is_empty()).then(|| field.to_owned())
+        };
+        config.style.font = optional(&self.font);
+        config.style.size = match optional(&self.size) {
+            Some(size) => match size.parse::<f64>() {
+                Ok(size) if size > 0.0 => Some(size),
+                _ => return Err(String::from("Text size must be a positive number")),
+            },
+            None => None,
+        };
+        config.style.lang = optional(&self.lang);
+        if let Some(lang) = &config.style.lang {
+            lang.parse::<Lang>()
+                .map_err(|err| format!("Invalid language: {err}"))?;
+        }
+        config.build.date = optional(&self.date);
+        if let Some(date) = &config.build.date {
+            NaiveDate::parse_from_str(date, DATE_FORMAT)
+                .map_err(|_| String::from("Date must be formatted as YYYY-MM-DD"))?;
+        }
+
+        Ok(config)
+    }
+
+    /// Returns the Iced view for the modal.
+    ///
+    /// Includes an editable list of inputs, fields for the default styles and the date,
+    /// and buttons to apply or cancel the changes.
+    pub fn view(&self) -> Element<'_, Message> {
+        let inputs = Column::with_children(self.inputs.iter().enumerate().map(
+            |(i, (key, value))| {
+                row![
+                    text_input("name", key)
+                        .on_input(move |key| Message::InputKey(i, key)),
+                    text_input("value", value)
+                        .on_input(move |value| Message::InputValue(i, value)),
+                    button(icon::trash().center())
+                        .on_press(Message::RemoveInput(i))
+                        .style(simple_button),
+                ]
+                .spacing(SPACING)
+                .align_y(Alignment::Center)
+                .into()
+            },
+        ))
+        .spacing(SPACING / 2.0);
+
+        let modal_content = container(
+            column![
+                text("Project Settings").size(20),
+                row![
+                    text("Inputs (sys.inputs)").width(Length::Fill),
+                    button(text("Add")).on_press(Message::AddInput),
+                ]
+                .align_y(Alignment::Center),
+                scrollable(inputs).height(Length::Fill),
+                text("Default styles"),
+                text_input("Font (e.g. Libertinus Serif)", &self.font)
+                    .on_input(Message::Font),
+                text_input("Text size in pt (e.g. 11)", &self.size)
+                    .on_input(Message::Size),
+                text_input("Language (e.g. en)", &self.lang).on_input(Message::Lang),
+                text("Reproducible builds"),
+                text_input("Fixed date (YYYY-MM-DD)", &self.date).on_input(Message::Date),
+                text(self.warning_text.clone())
+                    .size(15)
+                    .style(modal_text_style),
+                row![
+                    space().width(Length::Fill),
+                    button(text("Apply").center())
+                        .on_press(Message::Apply)
+                        .height(MODAL_BUTTON_HEIGHT)
+                        .width(MODAL_BUTTON_WIDTH)
+                        .style(validate_button),
+                    button(text("Cancel").center())
+                        .on_press(Message::Cancel)
+                        .height(MODAL_BUTTON_HEIGHT)
+                        .width(MODAL_BUTTON_WIDTH)
+                        .style(cancel_button),
+                ]
+                .spacing(SPACING),
+            ]
+            .spacing(SPACING),
+        )
+        .width(MODAL_WIDTH)
+        .height(MODAL_HEIGHT)
+        .padding(20)
+        .style(modal_style);
+
+        opaque(center(opaque(modal_content)))
+    }
+
+    /// Handles messages to update the internal state.
+    ///
+    /// Applying valid settings sends the new configuration to the editing screen.
+    pub fn update(
+        &mut self,
+        message: Message,
+    ) -> Task<editing::Message> {
+        match message {
+            Message::InputKey(i, key) => {
+                if let Some(input) = self.inputs.get_mut(i) {
+                    input.0 = key;
+                }
+            }
+            Message::InputValue(i, value) => {
+                if let Some(input) = self.inputs.get_mut(i) {
+                    input.1 = value;
+                }
+            }
+            Message::AddInput => {
+                self.inputs.push((String::new(), String::new()));
+            }
+            Message::RemoveInput(i) => {
+                if i < self.inputs.len() {
+                    self.inputs.remove(i);
+                }
+            }
+            Message::Font(font) => {
+                self.font = font;
+            }
+            Message::Size(size) => {
+                self.size = size;
+            }
+            Message::Lang(lang) => {
+                self.lang = lang;
+            }
+            Message::Date(date) => {
+                self.date = date;
+            }
+            Message::Apply => match self.to_config() {
+                Ok(config) => {
+                    self.hide();
+                    return Task::done(editing::Message::ProjectConfigured(Box::new(
+                        config,
+                    )));
+                }
+                Err(warning) => {
+                    self.warning_text = warning;
+                }
+            },
+            Message::Cancel => {
+                self.hide();
+            }
+        }
+        Task::none()
+    }
+}
@@ -0,0 +1,450 @@
+use crate::data::config::appearance::TypstConfig;
+use crate::data::style::button::{cancel_button, validate_button};
+use crate::data::style::file_tree::{direntry_button, direntry_selected_button};
+use crate::data::style::modal::{font_sample_style, modal_style, modal_text_style};
+use crate::file_manager::export::errors::ExportError;
+use crate::file_manager::export::template::{cached_thumbnail_path, render_thumbnail};
+use crate::file_manager::file::{get_templates_path, get_thumbnails_path};
+use crate::file_manager::template::{list_templates, Template};
+use crate::screen::editing::init_world;
+use crate::world::TideWorld;
+use iced::widget::image::Handle;
+use iced::widget::{
+    button, center, column, container, image, opaque, row, scrollable, space, text,
+    text_input, Column, Row,
+};
+use iced::{Alignment, Element, Length, Task};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const PANEL_WIDTH: f32 = 860.0;
+const PANEL_HEIGHT: f32 = 560.0;
+const SPACING: f32 = 10.0;
+const BUTTON_HEIGHT: f32 = 30.0;
+const CARD_WIDTH: f32 = 140.0;
+const CARD_THUMBNAIL_HEIGHT: f32 = 160.0;
+const CARDS_PER_ROW: usize = 3;
+const DETAILS_THUMBNAIL_HEIGHT: f32 = 240.0;
+
+/// Messages used in the context of the template gallery.
+#[derive(Debug, Clone)]
+pub enum Message {
+    /// Result of the initialization of the world rendering the thumbnails.
+    WorldLoaded(Box<TideWorld>),
+    /// Result of the rendering of the thumbnail of the template at the given path.
+    ThumbnailRendered(PathBuf, Result<Handle, ExportError>),
+    /// Triggered when the user selects the template at the given path.
+    Select(PathBuf),
+    /// Triggered when the name input of the selected template changes.
+    Name(String),
+    /// Renames the selected template with the name input.
+    Rename,
+    /// Copies the selected template as a new template.
+    Duplicate,
+    /// Deletes the selected template, once confirmed.
+    Delete,
+    /// Creates a project from the template at the given path.
+    ///
+    /// The gallery is hidden, the screen showing it opens the project creation modal.
+    Use(PathBuf),
+    /// Triggered when the gallery is closed.
+    Close,
+}
+
+/// A panel listing the local templates with their thumbnails,
+/// from which they are managed and new projects are created.
+pub struct TemplateGallery {
+    /// The panel visibility state.
+    pub visible: bool,
+    /// Configuration of the world rendering the thumbnails, when it must be created.
+    typst_config: TypstConfig,
+    /// World whose fonts and packages are used to render the thumbnails.
+    world: Option<TideWorld>,
+    /// Templates of the templates directory, sorted by name.
+    templates: Vec<Template>,
+    /// Thumbnail of each template, `None` if it can't be rendered.
+    /// Templates missing from the map are being rendered, the others keep their thumbnail
+    /// until it is rendered again.
+    thumbnails: HashMap<PathBuf, Option<Handle>>,
+    /// Path of the selected template.
+    selected: Option<PathBuf>,
+    /// Name input of the selected template.
+    name: String,
+    /// Warning text to display after a failed action.
+    warning_text: String,
+    /// Whether the deletion of the selected template was requested once.
+    confirm_delete: bool,
+}
+
+impl TemplateGallery {
+    /// Creates a new, hidden, [`TemplateGallery`].
+    pub fn new(typst_config: TypstConfig) -> Self {
+        Self {
+            visible: false,
+            typst_config,
+            world: None,
+            templates: vec![],
+            thumbnails: HashMap::new(),
+            selected: None,
+            name: String::new(),
+            warning_text: String::new(),
+            confirm_delete: false,
+        }
+    }
+
+    /// Makes the gallery visible and lists the templates.
+    ///
+    /// The thumbnails are rendered with the fonts and packages of `world`, or of a new world
+    /// initialized in the background if there is none.
+    pub fn show(
+        &mut self,
+        world: Option<&TideWorld>,
+    ) -> Task<Message> {
+        if let Some(world) = world {
+            self.world = Some(world.clone());
+        }
+        self.visible = true;
+        self.refresh()
+    }
+
+    /// Hides the gallery and forgets the selection. Thumbnails are kept for the next time.
+    fn hide(&mut self) {
+        self.visible = false;
+        self.selected = None;
+        self.name = String::new();
+        self.warning_text = String::new();
+        self.confirm_delete = false;
+    }
+
+    /// Lists the templates again and renders their thumbnails,
+    /// reusing the cached ones which are still up to date (see [`render_thumbnail`]).
+    fn refresh(&mut self) -> Task<Message> {
+        let Some(templates_path) = get_templates_path() else {
+            self.templates = vec![];
+            return Task::none();
+        };
+        self.templates = list_templates(&templates_path);
+        let templates = &self.templates;
+        self.thumbnails
+            .retain(|path, _| templates.iter().any(|template| &template.path == path));
+        if self.selected_template().is_none() {
+            self.selected = None;
+        }
+
+        match &self.world {
+            Some(_) => self.render_thumbnails(),
+            None => {
+                let config = self.typst_config.clone();
+                Task::perform(
+                    async move { init_world(&config, &templates_path) },
+                    |world| Message::WorldLoaded(Box::new(world)),
+                )
+            }
+        }
+    }
+
+    /// Returns the tasks rendering the thumbnails of the templates,
+    /// so that the thumbnails of modified templates are up to date.
+    fn render_thumbnails(&self) -> Task<Message> {
+        let (Some(world), Some(cache)) = (&self.world, get_thumbnails_path()) else {
+            return Task::none();
+        };
+        Task::batch(
+            self.templates
+                .iter()
+                .map(|template| {
+                    let path = template.path.clone();
+                    Task::perform(
+                        load_thumbnail(template.clone(), world.clone(), cache.clone()),
+                        move |result| Message::ThumbnailRendered(path.clone(), result),
+                    )
+                }),
+        )
+    }
+
+    /// Returns the selected template, if any.
+    fn selected_template(&self) -> Option<&Template> {
+        let selected = self.selected.as_ref()?;
+        self.templates
+            .iter()
+            .find(|template| &template.path == selected)
+    }
+
+    /// Returns the Iced view for the gallery.
+    ///
+    /// Shows the templates as cards on the left and details the selected one on the right.
+    pub fn view(&self) -> Element<'_, Message> {
+        let cards: Element<'_, Message> = if self.templates.is_empty() {
+            center(text(
+                "No template yet. Export a project with “Export as > Template”.",
+            ))
+            .into()
+        } else {
+            let rows = self.templates.chunks(CARDS_PER_ROW).map(|templates| {
+                Row::with_children(
+                    templates.iter().map(|template| self.view_card(template)),
+                )
+                .spacing(SPACING)
+                .into()
+            });
+            scrollable(Column::with_children(rows).spacing(SPACING))
+                .height(Length::Fill)
+                .into()
+        };
+
+        let details: Element<'_, Message> = match self.selected_template() {
+            Some(template) => self.view_details(template),
+            None => center(text("Select a template")).into(),
+        };
+
+        let panel = container(
+            column![
+                text("Templates").size(20),
+                row![
+                    container(cards)
+                        .width(CARDS_PER_ROW as f32 * (CARD_WIDTH + SPACING) + SPACING),
+                    details
+                ]
+                .spacing(SPACING * 2.0)
+                .height(Length::Fill),
+                row![
+                    space().width(Length::Fill),
+                    button(text("Close").center())
+                        .on_press(Message::Close)
+                        .height(BUTTON_HEIGHT)
+                        .style(cancel_button),
+                ],
+            ]
+            .spacing(SPACING),
+        )
+        .width(PANEL_WIDTH)
+        .height(PANEL_HEIGHT)
+        .padding(20)
+        .style(modal_style);
+
+        opaque(center(opaque(panel)))
+    }
+
+    /// Returns the card of a template: its thumbnail and name.
+    fn view_card<'a>(
+        &'a self,
+        template: &'a Template,
+    ) -> Element<'a, Message> {
+        let is_selected = self.selected.as_ref() == Some(&template.path);
+        button(
+            column![
+                self.view_thumbnail(template, CARD_THUMBNAIL_HEIGHT),
+                text(template.name()).size(14),
+            ]
+            .spacing(SPACING / 2.0)
+            .align_x(Alignment::Center),
+        )
+        .on_press(Message::Select(template.path.clone()))
+        .width(CARD_WIDTH)
+        .style(if is_selected {
+            direntry_selected_button
+        } else {
+            direntry_button
+        })
+        .into()
+    }
+
+    /// Returns the thumbnail of a template, or a placeholder.
+    fn view_thumbnail<'a>(
+        &'a self,
+        template: &Template,
+        height: f32,
+    ) -> Element<'a, Message> {
+        let thumbnail: Element<'a, Message> = match self.thumbnails.get(&template.path) {
+            Some(Some(handle)) => image(handle.clone()).height(height).into(),
+            Some(None) => center(text("No preview").size(14)).into(),
+            None => center(text("Rendering...").size(14)).into(),
+        };
+        container(thumbnail)
+            .width(Length::Fill)
+            .height(height)
+            .align_x(Alignment::Center)
+            .style(font_sample_style)
+            .into()
+    }
+
+    /// Returns the view detailing the selected template: its thumbnail, description and actions.
+    fn view_details<'a>(
+        &'a self,
+        template: &'a Template,
+    ) -> Element<'a, Message> {
+        let description = template.description().unwrap_or("No description.");
+        let delete_label = if self.confirm_delete {
+            "Confirm deletion"
+        } else {
+            "Delete"
+        };
+
+        column![
+            self.view_thumbnail(template, DETAILS_THUMBNAIL_HEIGHT),
+            row![
+                text_input("Template name", &self.name).on_input(Message::Name),
+                button(text("Rename").center())
+                    .on_press(Message::Rename)
+                    .height(BUTTON_HEIGHT)
+                    .style(validate_button),
+            ]
+            .spacing(SPACING),
+            text(description),
+            text(self.warning_text.as_str())
+                .size(15)
+                .style(modal_text_style),
+            space().height(Length::Fill),
+            row![
+                button(text("Duplicate").center())
+                    .on_press(Message::Duplicate)
+                    .height(BUTTON_HEIGHT)
+                    .style(validate_button),
+                button(text(delete_label).center())
+                    .on_press(Message::Delete)
+                    .height(BUTTON_HEIGHT)
+                    .style(cancel_button),
+                space().width(Length::Fill),
+                button(text("Create Project").center())
+                    .on_press(Message::Use(template.path.clone()))
+                    .height(BUTTON_HEIGHT)
+                    .style(validate_button),
+            ]
+            .spacing(SPACING),
+        ]
+        .spacing(SPACING)
+        .width(Length::Fill)
+        .into()
+    }
+
+    /// Handles messages to update the internal state.
+    pub fn update(
+        &mut self,
+        message: Message,
+    ) -> Task<Message> {
+        match message {
+            Message::WorldLoaded(world) => {
+                self.world = Some(*world);
+                self.render_thumbnails()
+            }
+            Message::ThumbnailRendered(path, result) => {
+                if let Err(err) = &result {
+                    eprintln!("can't render a thumbnail of {}: {err:?}", path.display());
+                }
+                self.thumbnails.insert(path, result.ok());
+                Task::none()
+            }
+            Message::Select(path) => {
+                self.name = self
+                    .templates
+                    .iter()
+                    .find(|template| template.path == path)
+                    .map(|template| template.name().to_string())
+                    .unwrap_or_default();
+                self.selected = Some(path);
+                self.warning_text = String::new();
+                self.confirm_delete = false;
+                Task::none()
+            }
+            Message::Name(name) => {
+                self.name = name;
+                Task::none()
+            }
+            Message::Rename => {
+                let Some(mut template) = self.selected_template().cloned() else {
+                    return Task::none();
+                };
+                let previous = template.path.clone();
+                match template.rename(&self.name) {
+                    Ok(()) => {
+                        if let Some(thumbnail) = self.thumbnails.remove(&previous) {
+                            self.thumbnails.insert(template.path.clone(), thumbnail);
+                        }
+                        move_cached_thumbnail(&previous, Some(&template.path));
+                        self.warning_text = String::new();
+                        self.selected = Some(template.path);
+                        self.refresh()
+                    }
+                    Err(err) => {
+                        self.warning_text = format!("Can't rename template: {err}");
+                        Task::none()
+                    }
+                }
+            }
+            Message::Duplicate => {
+                let Some(template) = self.selected_template() else {
+                    return Task::none();
+                };
+                match template.duplicate(&format!("{}-copy", template.name())) {
+                    Ok(copy) => {
+                        self.name = copy.name().to_string();
+                        self.warning_text = String::new();
+                        self.selected = Some(copy.path);
+                        self.refresh()
+                    }
+                    Err(err) => {
+                        self.warning_text = format!("Can't duplicate template: {err}");
+                        Task::none()
+                    }
+                }
+            }
+            Message::Delete => {
+                let Some(template) = self.selected_template().cloned() else {
+                    return Task::none();
+                };
+                if !self.confirm_delete {
+                    self.confirm_delete = true;
+                    return Task::none();
+                }
+                self.confirm_delete = false;
+                let path = template.path.clone();
+                match template.delete() {
+                    Ok(()) => {
+                        move_cached_thumbnail(&path, None);
+                        self.warning_text = String::new();
+                        self.selected = None;
+                        self.refresh()
+                    }
+                    Err(err) => {
+                        self.warning_text = format!("Can't delete template: {err}");
+                        Task::none()
+                    }
+                }
+            }
+            Message::Use(_) | Message::Close => {
+                self.hide();
+                Task::none()
+            }
+        }
+    }
+}
+
+/// Moves the cached thumbnail of the template previously at `previous` to its new path,
+/// or removes it if the template is deleted, so that it isn't reused by another template.
+fn move_cached_thumbnail(
+    previous: &Path,
+    path: Option<&Path>,
+) {
+    let Some(cache) = get_thumbnails_path() else {
+        return;
+    };
+    let cached = cached_thumbnail_path(&cache, previous);
+    let _ = match path {
+        Some(path) => fs::rename(cached, cached_thumbnail_path(&cache, path)),
+        None => fs::remove_file(cached),
+    };
+}
+
+/// Renders (or reuses) the thumbnail of `template` and loads it as an image.
+///
+/// See [`render_thumbnail`].
+async fn load_thumbnail(
+    template: Template,
+    world: TideWorld,
+    cache: PathBuf,
+) -> Result<Handle, ExportError> {
+    let path = render_thumbnail(template, world, cache).await?;
+    let bytes = std::fs::read(path).map_err(ExportError::FileReadError)?;
+    Ok(Handle::from_bytes(bytes))
+}
@@ -22,8 +22,12 @@ const MENU_BUTTON_SIZE: f32 = 50.0;
 pub enum Message {
     /// Forces the preview to regenerate.
     ForcePreview,
-    /// Toggles the visibility or style of the preview.
+    /// Toggles the inverted (dark) colors of the preview.
     TogglePreview,
+    /// Discovers the available fonts again, without restarting the application.
+    ReloadFonts,
+    /// Opens the font manager panel.
+    ShowFonts,
     /// Triggers file saving. The boolean indicates whether it's a "Save As".
     SaveFile(bool),
     /// Indicates the result of a file save operation.
@@ -44,12 +48,20 @@ pub enum Message {
     ThemeSelected(Theme),
     /// Opens a file from the system.
     OpenFile,
+    /// Opens the settings of the current project.
+    ProjectSettings,
     /// Opens a project from the system. Accepts optional path and main file path (if it's loaded from cache).
     OpenProject(Option<PathBuf>, Option<PathBuf>),
     /// Initiates an export operation.
     Export(ExportType),
+    /// Opens the options of an export operation before initiating it.
+    ExportOptions(ExportType),
+    /// Runs every export preset of the project.
+    ExportAll,
     /// Indicates the result of an export operation.
     ProjectExported(Result<PathBuf, ExportError>),
+    /// Enables or disables the export of the PDF after every save.
+    ToggleExportOnSave,
     /// Opens the Typst documentation online.
     Help,
     /// Opens the Typst Universe online.
@@ -67,8 +79,24 @@ pub enum Message {
 /// This toolbar includes drop-down menus for file management, theme switching,
 /// and export actions, as well as quick-access buttons for help, preview, and Typst Universe.
 ///
-/// The `main_path` is an optional reference to the currently selected main file (virtual path).
-pub fn editing_toolbar<'a>(main_path: Option<&VirtualPath>) -> Element<'a, Message> {
+/// The `main_path` is an optional reference to the currently selected main file (virtual path),
+/// `export_on_save` tells whether the PDF is exported after every save,
+/// and `dark_preview` whether the preview is displayed in inverted colors.
+pub fn editing_toolbar<'a>(
+    main_path: Option<&VirtualPath>,
+    export_on_save: bool,
+    dark_preview: bool,
+) -> Element<'a, Message> {
+    let export_on_save_label = if export_on_save {
+        "Stop Exporting on Save"
+    } else {
+        "Export PDF on Save"
+    };
+    let preview_colors_label = if dark_preview {
+        "Light Preview"
+    } else {
+        "Dark Preview"
+    };
     let menu_tpl_1 = |items| {
         Menu::new(items)
             .max_width(300.0)
@@ -84,21 +112,28 @@ pub fn editing_toolbar<'a>(main_path: Option<&VirtualPath>) -> Element<'a, Messa
                 (menu_button("Open File", Message::OpenFile)),
                 (menu_button("New Project", Message::NewProject)),
                 (menu_button("Open Project", Message::OpenProject(None, None))),
+                (menu_button("Project Settings", Message::ProjectSettings)),
                 (menu_button("Upload File", Message::Upload(UploadType::All, None))),
                 (menu_button("Save", Message::SaveFile(true))),
                 (menu_button("Save as", Message::SaveFile(true))),
                 (menu_button("Export as", Message::DropDownMenu), menu_tpl_1(menu_items!(
-                    (menu_button("PDF", Message::Export(ExportType::PDF))),
-                    (menu_button("SVG", Message::Export(ExportType::SVG))),
+                    (menu_button("PDF", Message::ExportOptions(ExportType::PDF))),
+                    (menu_button("SVG", Message::ExportOptions(ExportType::SVG))),
+                    (menu_button("PNG", Message::ExportOptions(ExportType::PNG))),
+                    (menu_button("HTML", Message::Export(ExportType::HTML))),
                     (menu_button("Template", Message::Export(ExportType::Template))),
+                    (menu_button("All Presets", Message::ExportAll)),
                 ))),
+                (menu_button(export_on_save_label, Message::ToggleExportOnSave)),
                 (menu_button("Add Local Template", Message::AddTemplate)),
                 (menu_button("Start from Template", Message::StartFromTemplate))
             )).width(240.0)
         }),
         (text_button("View", Message::DropDownMenu, MENU_BUTTON_SIZE), menu_tpl_1(menu_items!(
             (menu_button("Theme", Message::ChangeTheme)),
-            (menu_button("Invert", Message::TogglePreview))
+            (menu_button(preview_colors_label, Message::TogglePreview)),
+            (menu_button("Fonts", Message::ShowFonts)),
+            (menu_button("Reload Fonts", Message::ReloadFonts))
         )).width(240.0))
     );
 
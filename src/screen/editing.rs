@@ -1,31 +1,49 @@
 use super::component::{
     file_tree::{self},
     modal, pop_up,
-    preview::Preview,
+    preview::{self, Preview},
     toolbar::{self, editing_toolbar, open_url},
 };
 
 use crate::file_manager::import::{UploadType, ALL_TYPES, TEMPLATE};
+use crate::file_manager::watch::{watch_directory, Changes};
+use crate::screen::component::font_manager::{self, FontManager};
+use crate::screen::component::template_gallery::{self, TemplateGallery};
 use crate::screen::component::modal::{FileModal, ProjectModal};
+use crate::screen::component::export_options::{self, ExportOptions};
+use crate::screen::component::project_settings::{self, ProjectSettings};
 use crate::screen::component::pop_up::{PopUpElement, PopUpType};
 use crate::widgets::vsplit::Split;
-use crate::world::TideWorld;
+use crate::world::{fonts::PROJECT_FONTS_DIR, package::PackageStorage, TideWorld};
 use crate::{
-    data::config::appearance::EditorConfig, file_manager::file::load_repo_dialog,
+    data::config::appearance::{EditorConfig, TypstConfig},
+    data::config::project::{ProjectConfig, DATE_FORMAT},
+    file_manager::file::load_repo_dialog,
 };
 use crate::{
-    data::config::appearance::HighlighterTheme, file_manager::export::pdf::export_pdf,
+    data::config::appearance::HighlighterTheme,
+    file_manager::export::html::export_html,
+    file_manager::export::pdf::{default_ident, export_pdf, PdfExportOptions},
+    file_manager::export::png::{export_png, PngExportOptions},
+    file_manager::export::preset::export_preset,
+    file_manager::export::preview::compile_preview,
+};
+use crate::{
+    data::style::button::cancel_button,
+    file_manager::export::{parse_page_ranges, ExportType},
+};
+use crate::{
+    data::style::debug::{debug_container_style, error_text_style, warning_text_style},
+    widgets::vsplit,
 };
-use crate::{data::style::button::cancel_button, file_manager::export::ExportType};
-use crate::{data::style::debug::debug_container_style, widgets::vsplit};
 use crate::{editor, file_manager::export::errors::ExportError};
 use crate::{
     editor::autocomplete::autocomplete, file_manager::file::delete_file_from_disk,
 };
-use crate::{editor::bindings::bindings, file_manager::import::load::load_repo};
+use crate::editor::bindings::bindings;
 use crate::{editor::buffer::Buffer, file_manager::import::load::load_file};
 use crate::{
-    file_manager::export::svg::{export_svg, preview_svg},
+    file_manager::export::svg::{export_svg, SvgExportOptions},
     font::EDITOR_FONT_FAMILY_NAME,
 };
 use crate::{
@@ -34,36 +52,47 @@ use crate::{
 };
 use crate::{
     file_manager::file::{
-        cache_project, get_templates_path, load_file_dialog, save_file_dialog,
-        save_file_disk, ProjectCache,
+        cache_project, get_fonts_path, get_templates_path, load_file_dialog,
+        save_file_dialog, save_file_disk, ProjectCache,
     },
     screen::component::file_tree::FileTree,
 };
 use iced::{
-    advanced::svg::Handle,
     widget::{
-        button, column, container, row, space, stack, svg, text, center,
-        text_editor::{self, Action, Binding, Edit, Motion},
+        button, column, container, row, space, stack, text, center,
+        operation,
+        text_editor::{self, Action, Binding, Cursor, Edit, Motion, Position},
         Column, Scrollable, TextEditor,
     },
-    Alignment, Element, Font, Length, Task,
+    Alignment, Element, Font, Length, Subscription, Task, Theme,
 };
+use chrono::{DateTime, Local, Utc};
 use iced_aw::SelectionList;
 use std::io::{Error, ErrorKind};
 use std::sync::Arc;
-use std::{collections::HashMap, fs, path::PathBuf};
+use std::time::Duration;
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    fs,
+    path::{Path, PathBuf},
+};
 use std::{
     fmt::{Display, Formatter},
     hash::{Hash, Hasher},
 };
+use typst::layout::PagedDocument;
 use typst::syntax::{FileId, VirtualPath};
 use typst::World;
 use typst::{
-    diag::SourceDiagnostic,
+    diag::{Severity, SourceDiagnostic, Warned},
     ecow::{EcoString, EcoVec},
 };
-use typst_ide::Completion;
-use typst_pdf::PdfOptions;
+use typst_ide::{Completion, Jump};
+
+/// Id of the text editor, to focus it after jumping to a source location.
+const EDITOR_ID: &str = "editor";
+/// Delay without changes in the font directories after which the fonts are reloaded.
+const FONTS_RELOAD_DELAY: Duration = Duration::from_millis(500);
 
 /// Represents the current editing state of Tide.
 ///
@@ -93,11 +122,38 @@ pub struct Editing {
     pop_up: Option<PopUpElement>,
     /// Debug UI zone (e.g. diagnostics, logs).
     debug: Option<EcoVec<SourceDiagnostic>>,
+    /// Warnings of the last compilation of the preview, even if it failed.
+    warnings: EcoVec<SourceDiagnostic>,
     /// Modal window for creating a file.
     file_modal: FileModal,
     /// Modal window for creating a new project.
     project_modal: ProjectModal,
+    /// Panel listing the fonts known to the Typst world.
+    font_manager: FontManager,
+    /// Panel listing the local templates.
+    template_gallery: TemplateGallery,
+    /// Configuration of the current project.
+    project: ProjectConfig,
+    /// Modal window for editing the configuration of the project.
+    project_settings: ProjectSettings,
+    /// Modal window for choosing the options of an export.
+    export_options: ExportOptions,
+    /// Destination and options of the last PDF export, reused by the exports on save.
+    last_pdf_export: Option<(PathBuf, PdfExportOptions)>,
+    /// Outcome of the last export, shown in the status bar.
+    export_status: Option<ExportStatus>,
     theme: HighlighterTheme,
+    /// Whether fonts are reloaded automatically when a `fonts` directory changes.
+    watch_fonts: bool,
+    /// Number of the last change of the font directories, to reload the fonts once.
+    font_changes: u64,
+    /// Delay without typing after which the preview is compiled,
+    /// `None` if the live preview is disabled.
+    live_preview: Option<Duration>,
+    /// Number of the last edit, to compile the live preview once typing stops.
+    edits: u64,
+    /// Number of the last compilation of the preview, to discard older results.
+    compilations: u64,
 }
 
 impl Editing {
@@ -106,24 +162,43 @@ impl Editing {
     /// Initializes the Typst world, file tree, and UI context.
     pub fn new(
         config: EditorConfig,
+        typst_config: TypstConfig,
         current_dir: PathBuf,
     ) -> Self {
         println!("{:?}", config.colors);
+        let project = ProjectConfig::load(&current_dir);
+        let mut typst = init_world(&typst_config, &current_dir);
+        typst.configure(&project);
         Self {
             current: Current::empty(),
             buffers: HashMap::new(),
             current_dir: current_dir.clone(),
-            preview: Preview::new(),
+            preview: Preview::new(&project.preview),
             file_tree: FileTree::new(&current_dir, None, None),
-            typst: init_world(),
+            typst,
             auto_pairs: config.auto_pairs,
             split_at: (250.0, 800.0),
             autocompletion_ctx: AutocompletionContext::new(),
             pop_up: None,
             debug: None,
+            warnings: EcoVec::new(),
             file_modal: FileModal::new(current_dir.to_path_buf()),
             project_modal: ProjectModal::new(),
+            font_manager: FontManager::new(),
+            template_gallery: TemplateGallery::new(typst_config.clone()),
+            project,
+            project_settings: ProjectSettings::new(),
+            export_options: ExportOptions::new(),
+            last_pdf_export: None,
+            export_status: None,
             theme: config.colors,
+            watch_fonts: typst_config.watch_fonts,
+            font_changes: 0,
+            live_preview: config
+                .live_preview
+                .then(|| Duration::from_millis(config.live_preview_delay)),
+            edits: 0,
+            compilations: 0,
         }
     }
 
@@ -149,6 +224,270 @@ impl Editing {
         self.typst.reload_source_from_content(id, &buffer.content);
         if let Some(buf) = self.buffers.get_mut(&id) {
             buf.content = buffer.content;
+            buf.is_saved = buffer.is_saved;
+        }
+    }
+
+    /// Returns the open [`Buffer`] of the given file, if any.
+    fn buffer(
+        &self,
+        id: FileId,
+    ) -> Option<&Buffer> {
+        if self.current.file_id == Some(id) {
+            Some(&self.current.buffer)
+        } else {
+            self.buffers.get(&id)
+        }
+    }
+
+    /// Replaces the buffer of the given file with its content on disk.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if the file can't be read.
+    fn reload_buffer(
+        &mut self,
+        id: FileId,
+        path: &PathBuf,
+    ) -> Result<(), Error> {
+        let buffer = Buffer::from_path(path)?;
+        self.typst.reload_source_from_content(id, &buffer.content);
+        if self.current.file_id == Some(id) {
+            self.current.buffer = buffer.clone();
+        }
+        self.buffers.insert(id, buffer);
+        Ok(())
+    }
+
+    /// Synchronizes the editor with a file changed outside of Tide.
+    ///
+    /// Saved buffers are reloaded silently. For buffers with unsaved changes,
+    /// returns a [`Task`] asking the user whether to reload the file or keep the buffer.
+    fn file_changed(
+        &mut self,
+        path: PathBuf,
+    ) -> Option<Task<Message>> {
+        let id = TideWorld::id_from_path(&path, &self.current_dir)?;
+        self.typst.invalidate(id);
+
+        let buffer = self.buffer(id)?;
+        //the file may have been deleted, or changed by our own save
+        let disk_content = fs::read_to_string(&path).ok()?;
+        if disk_content == buffer.content.text() {
+            return None;
+        }
+        if buffer.is_saved {
+            if let Err(err) = self.reload_buffer(id, &path) {
+                eprintln!("can't reload {:?}: {}", path, err);
+            }
+            return None;
+        }
+        Some(Task::done(Message::PopUp(pop_up::Message::ShowPopUp(
+            PopUpElement::new(
+                PopUpType::Conflict(id),
+                String::from("File changed on disk"),
+                format!(
+                    "{}\nReload it and lose your unsaved changes?",
+                    path.to_string_lossy()
+                ),
+            ),
+        ))))
+    }
+
+    /// Returns the [`Subscription`]s watching the project directory for external changes
+    /// and, if enabled, the user fonts directory, and listening to the preview shortcuts.
+    pub fn subscription(&self) -> Subscription<Message> {
+        let project = watch_directory(self.current_dir.clone()).map(Message::FilesChanged);
+        let user_fonts = match get_fonts_path() {
+            Some(path) if self.watch_fonts => watch_directory(path)
+                .map(|_| Message::FontsChanged),
+            _ => Subscription::none(),
+        };
+        let preview = self.preview.subscription().map(Message::Preview);
+        Subscription::batch([project, user_fonts, preview])
+    }
+
+    /// Returns the options of the PDF exports of the project, as configured in `tide.toml`.
+    ///
+    /// The creation date is the fixed time of a reproducible project, or the current time.
+    fn pdf_options(&self) -> PdfExportOptions {
+        let pdf = &self.project.pdf;
+        let pages = pdf.pages.as_deref().and_then(|pages| {
+            parse_page_ranges(pages).unwrap_or_else(|err| {
+                eprintln!("Ignoring the PDF page ranges of the project: {err}");
+                None
+            })
+        });
+        PdfExportOptions::from_project(pdf, &self.typst, pages)
+    }
+
+    /// Returns the destination of the exports on save: the path of the last PDF export,
+    /// or the one saved in the project configuration.
+    fn pdf_output(&self) -> Option<PathBuf> {
+        self.last_pdf_export
+            .as_ref()
+            .map(|(path, _)| path.clone())
+            .or_else(|| {
+                let output = self.project.pdf.output.as_ref()?;
+                Some(self.current_dir.join(output))
+            })
+    }
+
+    /// Returns a task exporting the PDF at `path` with `options`,
+    /// remembered as the destination and options of the exports on save.
+    ///
+    /// The destination saved in the project configuration is left unchanged:
+    /// it is only set when the exports on save are enabled.
+    fn export_pdf_to(
+        &mut self,
+        path: PathBuf,
+        options: PdfExportOptions,
+    ) -> Task<Message> {
+        let path = path.with_extension("pdf");
+        self.last_pdf_export = Some((path.clone(), options.clone()));
+        Task::perform(export_pdf(self.typst.clone(), path, options), |result| {
+            Message::ToolBar(toolbar::Message::ProjectExported(result))
+        })
+    }
+
+    /// Returns a task exporting the PDF to the destination of the exports on save,
+    /// if they are enabled.
+    ///
+    /// See [`Editing::export_on_save_options`] for the options of the export.
+    fn export_on_save(&self) -> Task<Message> {
+        if !self.project.pdf.export_on_save {
+            return Task::none();
+        }
+        let Some(path) = self.pdf_output() else {
+            return Task::none();
+        };
+        let options = self.export_on_save_options();
+        Task::perform(
+            export_pdf(self.typst.clone(), path, options),
+            Message::ExportedOnSave,
+        )
+    }
+
+    /// Returns the options of the exports on save: the options of the last PDF export,
+    /// or the project defaults before any.
+    ///
+    /// The creation date is computed again for each export, as the fixed time
+    /// of a reproducible project or the current time.
+    fn export_on_save_options(&self) -> PdfExportOptions {
+        let options = match &self.last_pdf_export {
+            Some((_, options)) => options.clone(),
+            None => self.pdf_options(),
+        };
+        PdfExportOptions {
+            timestamp: Some(self.typst.timestamp().unwrap_or_else(Utc::now)),
+            ..options
+        }
+    }
+
+    /// Saves whether the PDF is exported on save, and its destination, in `tide.toml`.
+    ///
+    /// Returns a task showing a pop-up if the project configuration can't be written.
+    fn save_export_on_save(&self) -> Task<Message> {
+        match self.project.save_export_on_save(&self.current_dir) {
+            Ok(_) => Task::none(),
+            Err(err) => Task::done(Message::PopUp(pop_up::Message::ShowPopUp(
+                PopUpElement::new(
+                    PopUpType::Error,
+                    String::from("Project settings not saved!"),
+                    err.to_string(),
+                ),
+            ))),
+        }
+    }
+
+    /// Returns `path` relative to the project root if it is inside it, as saved in `tide.toml`.
+    fn relative_output(
+        &self,
+        path: &Path,
+    ) -> String {
+        path.strip_prefix(&self.current_dir)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    /// Returns a task running every export preset of the project concurrently.
+    ///
+    /// Their results are collected in a single [`Message::PresetsExported`].
+    fn export_presets(&self) -> Task<Message> {
+        let main = self
+            .typst
+            .main()
+            .vpath()
+            .as_rootless_path()
+            .file_stem()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let date = self
+            .typst
+            .timestamp()
+            .map(|time| time.date_naive())
+            .unwrap_or_else(|| Local::now().date_naive())
+            .format(DATE_FORMAT)
+            .to_string();
+        let exports = self.project.presets.iter().map(|preset| {
+            let name = preset.name.clone();
+            let output_path = preset.output_path(&self.current_dir, &main, &date);
+            Task::perform(
+                export_preset(
+                    self.typst.clone(),
+                    preset.clone(),
+                    output_path,
+                    self.pdf_options(),
+                ),
+                move |result| (name, result),
+            )
+        });
+        Task::batch(exports)
+            .collect()
+            .map(Message::PresetsExported)
+    }
+
+    /// Scrolls the preview to the output of the text under the cursor, highlighting it.
+    fn sync_preview(&mut self) -> Task<Message> {
+        let Some(id) = self.current_file_id() else {
+            return Task::none();
+        };
+        let Ok(source) = self.typst.source(id) else {
+            return Task::none();
+        };
+        let position = self.current_buffer().content.cursor().position;
+        //the columns of the editor are byte offsets within the line
+        match source.lines().line_to_byte(position.line) {
+            Some(start) => self
+                .preview
+                .reveal_cursor(&source, start + position.column)
+                .map(Message::Preview),
+            None => Task::none(),
+        }
+    }
+
+    /// Compiles the preview in the background, from the current content of the editor.
+    ///
+    /// The last preview stays displayed until the compilation ends, and the results of
+    /// compilations started before the last one are discarded.
+    fn compile(&mut self) -> Task<Message> {
+        if let Some(id) = self.current_file_id() {
+            self.update_source(id, self.current_buffer().clone());
+        }
+        self.compilations += 1;
+        let compilation = self.compilations;
+        Task::perform(compile_preview(self.typst.clone()), move |result| {
+            Message::PreviewCompiled(compilation, Box::new(result))
+        })
+    }
+
+    /// Recompiles the preview, only if one is already displayed.
+    fn refresh_preview(&self) -> Task<Message> {
+        if self.preview.is_loaded() {
+            Task::done(Message::ToolBar(toolbar::Message::ForcePreview))
+        } else {
+            Task::none()
         }
     }
 
@@ -158,11 +497,14 @@ impl Editing {
     }
 
     /// Closes the buffer associated with the given [`FileId`].
+    ///
+    /// Its unsaved changes are discarded and the file is compiled from disk again.
     fn close_buffer(
         &mut self,
         id: FileId,
     ) {
         self.buffers.remove(&id);
+        self.typst.close_source(id);
     }
 
     /// Deletes a file both from disk and from the Typst world.
@@ -232,9 +574,14 @@ impl Editing {
     /// Composes the file tree, text editor, preview (if available), status bar,
     /// and optional modals or pop-ups.
     pub fn view(&self) -> Element<'_, Message> {
-        let tool_bar =
-            editing_toolbar(Some(self.typst.main().vpath())).map(Message::ToolBar);
+        let tool_bar = editing_toolbar(
+            Some(self.typst.main().vpath()),
+            self.project.pdf.export_on_save,
+            self.preview.is_inverted(),
+        )
+        .map(Message::ToolBar);
         let editor = TextEditor::new(&self.current_buffer().content)
+            .id(EDITOR_ID)
             .on_action(Message::ActionPerformed)
             .placeholder("Insert text here or open a new file")
             .key_binding(|key_press| {
@@ -291,7 +638,7 @@ impl Editing {
 
         let mut edit_col = Column::new().push(editor);
         if let Some(debug) = &self.debug {
-            edit_col = edit_col.push(view_errors(debug));
+            edit_col = edit_col.push(view_errors(debug, &self.typst));
         } //debug
 
         let mut main_screen = Split::new(
@@ -302,18 +649,10 @@ impl Editing {
         )
         .strategy(vsplit::Strategy::Start);
 
-        if let Some(svg_handles) = &self.preview.handle {
-            let mut svg_pages = vec![];
-            for page in svg_handles.to_owned() {
-                svg_pages.push(svg(page).into());
-            }
-            let preview =
-                Scrollable::new(Column::with_children(svg_pages).spacing(15).padding(15))
-                    .width(Length::Fill)
-                    .height(Length::Fill);
+        if self.preview.is_loaded() {
             main_screen = Split::new(
                 main_screen,
-                preview,
+                self.preview.view().map(Message::Preview),
                 self.split_at.1,
                 Message::ResizePreview,
             )
@@ -332,6 +671,8 @@ impl Editing {
                 None => "No file selected".to_string(),
             },
             self.current.buffer.is_saved,
+            self.export_status.as_ref().map(ExportStatus::to_string),
+            self.warnings.len(),
         ); //status bar
 
         let screen = column![tool_bar, main_screen, status_bar];
@@ -361,6 +702,32 @@ impl Editing {
                 .into();
         } //"new project" modal
 
+        if self.project_settings.visible {
+            return stack![
+                screen,
+                self.project_settings.view().map(Message::ProjectSettings)
+            ]
+            .into();
+        } //project settings modal
+
+        if self.export_options.visible {
+            return stack![screen, self.export_options.view().map(Message::ExportOptions)]
+                .into();
+        } //export options modal
+
+        if self.font_manager.visible {
+            return stack![screen, self.font_manager.view().map(Message::FontManager)]
+                .into();
+        } //font manager
+
+        if self.template_gallery.visible {
+            return stack![
+                screen,
+                self.template_gallery.view().map(Message::TemplateGallery)
+            ]
+            .into();
+        } //template gallery
+
         screen.into() //default
     }
 
@@ -410,7 +777,57 @@ impl Editing {
                             )),
                         )),
                     },
+                    pop_up::Message::ReloadFile(id) => {
+                        self.pop_up = None;
+                        let Some(path) = id.vpath().resolve(&self.current_dir) else {
+                            return Task::none();
+                        };
+                        match self.reload_buffer(id, &path) {
+                            Ok(_) => Task::none(),
+                            Err(err) => Task::done(Message::PopUp(
+                                pop_up::Message::ShowPopUp(PopUpElement::new(
+                                    PopUpType::Error,
+                                    String::from("Can't reload file!"),
+                                    err.to_string(),
+                                )),
+                            )),
+                        }
+                    }
+                }
+            }
+            Message::FilesChanged(Changes {
+                paths,
+                entries_changed,
+            }) => {
+                //modified files don't change the tree
+                if entries_changed {
+                    self.file_tree.refresh();
+                }
+                let project_fonts_path = self.current_dir.join(PROJECT_FONTS_DIR);
+                let fonts_changed = self.watch_fonts
+                    && paths.iter().any(|path| path.starts_with(&project_fonts_path));
+                let mut tasks: Vec<_> = paths
+                    .into_iter()
+                    .filter_map(|path| self.file_changed(path))
+                    .collect();
+                if fonts_changed {
+                    tasks.push(Task::done(Message::FontsChanged));
                 }
+                Task::batch(tasks)
+            }
+            Message::FontsChanged => {
+                //copying fonts emits many events, the fonts are reloaded once they settle
+                self.font_changes += 1;
+                let change = self.font_changes;
+                Task::perform(tokio::time::sleep(FONTS_RELOAD_DELAY), move |_| {
+                    Message::FontsSettled(change)
+                })
+            }
+            Message::FontsSettled(change) => {
+                if change != self.font_changes {
+                    return Task::none();
+                }
+                Task::done(Message::ToolBar(toolbar::Message::ReloadFonts))
             }
             Message::ShowErrors(err) => {
                 self.debug = Some(err);
@@ -482,70 +899,169 @@ impl Editing {
             }
             Message::ActionPerformed(action) => {
                 let is_edit = action.is_edit();
+                //edits make the previewed document outdated, so only moves are followed
+                let moves_cursor = !is_edit
+                    && !matches!(action, Action::Scroll { .. } | Action::Drag(_));
                 self.current.buffer.content.perform(action);
                 if self.current.buffer.is_saved && is_edit {
                     self.current.buffer.is_saved = false;
                 }
-                Task::none()
+                match self.live_preview {
+                    Some(delay) if is_edit => {
+                        self.edits += 1;
+                        let edit = self.edits;
+                        Task::perform(tokio::time::sleep(delay), move |_| {
+                            Message::LivePreview(edit)
+                        })
+                    }
+                    _ if moves_cursor => self.sync_preview(),
+                    _ => Task::none(),
+                }
             }
-            Message::SvgGenerated(result) => {
-                println!("async: SVG generated");
-                match result {
-                    Ok(svg) => Task::perform(
-                        async move {
-                            let mut svg_handles: Vec<Handle> = vec![];
-                            for content in svg {
-                                svg_handles
-                                    .push(Handle::from_memory(content.into_bytes()));
-                            }
-                            svg_handles
-                        },
-                        Message::PreviewLoaded,
-                    ),
+            Message::LivePreview(edit) => {
+                //typing didn't stop yet, or no preview is displayed to refresh
+                if edit != self.edits || !self.preview.is_loaded() {
+                    return Task::none();
+                }
+                self.compile()
+            }
+            Message::PreviewCompiled(compilation, result) => {
+                //a newer compilation is running, this one is outdated
+                if compilation != self.compilations {
+                    return Task::none();
+                }
+                println!("async: preview compiled");
+                //the invalid settings of the project are reported with the warnings
+                let config_warnings = self.typst.config_warnings();
+                match *result {
+                    Ok(Warned {
+                        output: document,
+                        warnings,
+                    }) => {
+                        self.warnings =
+                            warnings.into_iter().chain(config_warnings.clone()).collect();
+                        let errors = if self.warnings.is_empty() {
+                            Task::done(Message::HideErrors)
+                        } else {
+                            Task::done(Message::ShowErrors(self.warnings.clone()))
+                        };
+                        Task::batch([
+                            self.preview.set_document(document).map(Message::Preview),
+                            errors,
+                        ])
+                    }
                     Err(err) => match err {
                         ExportError::CompilationError(err) => {
-                            Task::done(Message::ShowErrors(err))
+                            let diagnostics: EcoVec<_> =
+                                err.into_iter().chain(config_warnings.clone()).collect();
+                            self.warnings = diagnostics
+                                .iter()
+                                .filter(|diagnostic| diagnostic.severity == Severity::Warning)
+                                .cloned()
+                                .collect();
+                            Task::done(Message::ShowErrors(diagnostics))
                         }
                         _ => Task::none(),
                     },
                 }
             }
-            Message::PreviewLoaded(svg_handles) => {
-                println!("async: preview loaded");
-                self.preview.handle = Some(svg_handles);
-                Task::done(Message::HideErrors)
+            Message::Preview(preview::Message::Clicked(page, click)) => {
+                match self.preview.jump_from_click(&self.typst, page, click) {
+                    Some(Jump::File(id, offset)) => {
+                        Task::done(Message::JumpToSource(id, offset))
+                    }
+                    Some(Jump::Url(url)) => {
+                        open_url(&url);
+                        Task::none()
+                    }
+                    Some(Jump::Position(position)) => {
+                        self.preview.scroll_to_position(&position).map(Message::Preview)
+                    }
+                    None => Task::none(),
+                }
+            }
+            Message::JumpToSource(id, offset) => {
+                let mut task = Task::none();
+                if self.current_file_id() != Some(id) {
+                    //sources of packages can't be edited
+                    if id.package().is_some() {
+                        return Task::none();
+                    }
+                    let path = self.current_dir.join(id.vpath().as_rootless_path());
+                    if let Entry::Vacant(entry) = self.buffers.entry(id) {
+                        match Buffer::from_path(&path) {
+                            Ok(buffer) => {
+                                entry.insert(buffer);
+                            }
+                            Err(error) => {
+                                return Task::done(Message::PopUp(
+                                    pop_up::Message::ShowPopUp(PopUpElement::new(
+                                        PopUpType::Warning,
+                                        String::from("Can't open file"),
+                                        format!("{error}\n{:?}", path),
+                                    )),
+                                ));
+                            }
+                        }
+                    }
+                    //the buffer is loaded, so the file changes right away
+                    task = self.update(Message::FileTree(
+                        file_tree::Message::ChangeCurrentFile(path),
+                    ));
+                    if self.current_file_id() != Some(id) {
+                        return task;
+                    }
+                }
+                let Ok(source) = self.typst.source(id) else {
+                    return task;
+                };
+                //the columns of the editor are byte offsets within the line
+                let lines = source.lines();
+                let position = lines.byte_to_line(offset).and_then(|line| {
+                    lines.line_to_byte(line).map(|start| (line, offset - start))
+                });
+                if let Some((line, column)) = position {
+                    self.current.buffer.content.move_to(Cursor {
+                        position: Position { line, column },
+                        selection: None,
+                    });
+                }
+                Task::batch([task, operation::focus(EDITOR_ID)])
+            }
+            Message::Preview(message) => {
+                //the zoom is saved once the preview settles, not at every step
+                let is_idle = matches!(message, preview::Message::Idle(_));
+                let task = self.preview.update(message).map(Message::Preview);
+                if is_idle && self.preview.zoom() != self.project.preview.zoom {
+                    self.project.preview.zoom = self.preview.zoom();
+                    if let Err(err) = self.project.save_preview(&self.current_dir) {
+                        eprintln!("Preview zoom not saved: {err}");
+                    }
+                }
+                task
             }
             Message::ToolBar(message) => {
                 match message {
-                    toolbar::Message::StartFromTemplate => {
-                        let templates_path =
-                            get_templates_path().unwrap_or(self.current_dir.to_owned());
-                        if let Some(template) =
-                            load_file_dialog(&templates_path, "Typst template", &TEMPLATE)
-                        {
-                            self.project_modal.require_template(template);
-                            return Task::done(Message::ToolBar(
-                                toolbar::Message::NewProject,
-                            ));
+                    toolbar::Message::StartFromTemplate => self
+                        .template_gallery
+                        .show(Some(&self.typst))
+                        .map(Message::TemplateGallery),
+                    toolbar::Message::ForcePreview => self.compile(),
+                    toolbar::Message::TogglePreview => {
+                        let inverted = !self.preview.is_inverted();
+                        self.project.preview.inverted = inverted;
+                        if let Err(err) = self.project.save_preview(&self.current_dir) {
+                            eprintln!("Preview colors not saved: {err}");
                         }
-                        Task::done(Message::PopUp(pop_up::Message::ShowPopUp(
-                            PopUpElement::new(
-                                PopUpType::Warning,
-                                String::from("Project creation cancelled!"),
-                                String::from(
-                                    "We can't create your project without template.",
-                                ),
-                            ),
-                        )))
+                        self.preview.set_inverted(inverted).map(Message::Preview)
                     }
-                    toolbar::Message::ForcePreview => {
-                        if let Some(id) = self.current_file_id() {
-                            self.update_source(id, self.current_buffer().clone());
-                        }
-                        Task::perform(
-                            preview_svg(self.typst.clone()),
-                            Message::SvgGenerated,
-                        )
+                    toolbar::Message::ReloadFonts => {
+                        self.typst.reload_fonts();
+                        self.refresh_preview()
+                    }
+                    toolbar::Message::ProjectSettings => {
+                        self.project_settings.show(&self.project);
+                        Task::none()
                     }
                     toolbar::Message::SaveFile(update) => {
                         if let Some(id) = self.current_file_id() {
@@ -570,7 +1086,8 @@ impl Editing {
                         Ok(path) => {
                             println!("file saved at {:?}", path);
                             self.current.buffer.is_saved = true;
-                            Task::none()
+                            //only what was actually saved is exported
+                            self.export_on_save()
                         }
                         Err(err) => Task::done(Message::PopUp(
                             pop_up::Message::ShowPopUp(PopUpElement::new(
@@ -651,20 +1168,25 @@ impl Editing {
                         self.file_tree = FileTree::new(&path, main.clone(), None);
                         self.file_modal = FileModal::new(path.to_path_buf());
 
-                        // load files in TideWorld
-                        if let Ok(imported_files) = load_repo(&path, &self.current_dir) {
-                            for imported_file in imported_files {
-                                self.typst.add_file(imported_file);
-                            }
-                        }
-
-                        Task::perform(
-                            cache_project(ProjectCache::new(
-                                self.current_dir.to_owned(),
-                                main.clone(),
-                            )),
-                            move |()| Message::CachedProject(main.clone()),
-                        )
+                        // files are loaded lazily by TideWorld from the new root
+                        self.project = ProjectConfig::load(&path);
+                        self.typst.change_root(path);
+                        self.typst.configure(&self.project);
+                        let zoom = self.preview.set_zoom(self.project.preview.zoom);
+                        let colors =
+                            self.preview.set_inverted(self.project.preview.inverted);
+
+                        Task::batch([
+                            zoom.map(Message::Preview),
+                            colors.map(Message::Preview),
+                            Task::perform(
+                                cache_project(ProjectCache::new(
+                                    self.current_dir.to_owned(),
+                                    main.clone(),
+                                )),
+                                move |()| Message::CachedProject(main.clone()),
+                            ),
+                        ])
                     }
                     // TODO: write the real open file
                     toolbar::Message::OpenFile => {
@@ -693,60 +1215,77 @@ impl Editing {
                             ExportType::PDF => {
                                 let path = save_file_dialog("pdf", &["pdf"]);
                                 if let Some(export_path) = path {
-                                    Task::perform(
-                                        export_pdf(
-                                            self.typst.clone(),
-                                            export_path,
-                                            PdfOptions::default(),
-                                        ),
-                                        |result| {
-                                            Message::ToolBar(
-                                                toolbar::Message::ProjectExported(result),
-                                            )
-                                        },
-                                    )
+                                    self.export_pdf_to(export_path, self.pdf_options())
                                 } else {
                                     Task::none() //abort or error
                                 }
                             }
                             ExportType::SVG => {
-                                let path = save_file_dialog("svg", &["svg"]);
+                                self.export_options.show_svg(&self.project);
+                                Task::none()
+                            }
+                            ExportType::PNG => {
+                                self.export_options.show_png(&self.project);
+                                Task::none()
+                            }
+                            ExportType::HTML => {
+                                let path = save_file_dialog("html", &["html"]);
                                 if let Some(export_path) = path {
                                     Task::perform(
-                                        export_svg(self.typst.clone(), export_path),
-                                        |result| {
-                                            Message::ToolBar(
-                                                toolbar::Message::ProjectExported(result),
-                                            )
-                                        },
+                                        export_html(self.typst.clone(), export_path),
+                                        Message::HtmlExported,
                                     )
                                 } else {
                                     Task::none() //abort or error
                                 }
                             }
                             ExportType::Template => {
-                                let export_path = get_templates_path()
-                                    .unwrap_or(self.current_dir.to_owned());
-                                let file_path = self.file_tree.selected_path.to_owned();
-                                if let Some(current_path) = file_path {
-                                    return Task::perform(
-                                        export_template(current_path, export_path),
-                                        |result| {
-                                            Message::ToolBar(
-                                                toolbar::Message::ProjectExported(result),
-                                            )
-                                        },
-                                    );
-                                }
-                                Task::done(Message::PopUp(pop_up::Message::ShowPopUp(
+                                let Some(templates_path) = get_templates_path() else {
+                                    return Task::done(Message::PopUp(
+                                        pop_up::Message::ShowPopUp(PopUpElement::new(
+                                            PopUpType::Error,
+                                            String::from("Can't export template!"),
+                                            String::from("The templates directory is missing."),
+                                        )),
+                                    ));
+                                };
+                                Task::perform(
+                                    export_template(self.typst.clone(), templates_path),
+                                    |result| {
+                                        Message::ToolBar(toolbar::Message::ProjectExported(
+                                            result,
+                                        ))
+                                    },
+                                )
+                            }
+                        }
+                    }
+                    toolbar::Message::ExportOptions(export_type) => {
+                        match export_type {
+                            ExportType::PDF => self
+                                .export_options
+                                .show_pdf(&self.project, default_ident(&self.typst)),
+                            ExportType::PNG => self.export_options.show_png(&self.project),
+                            ExportType::SVG => self.export_options.show_svg(&self.project),
+                            _ => {
+                                return Task::done(Message::ToolBar(
+                                    toolbar::Message::Export(export_type),
+                                ))
+                            }
+                        }
+                        Task::none()
+                    }
+                    toolbar::Message::ExportAll => {
+                        if self.project.presets.is_empty() {
+                            return Task::done(Message::PopUp(pop_up::Message::ShowPopUp(
                                 PopUpElement::new(
                                     PopUpType::Warning,
-                                    String::from("No file selected!"),
-                                    String::from("Please select a file to export it as template."),
+                                    String::from("No export preset!"),
+                                    String::from("Define them as [[presets]] in the tide.toml of the project."),
                                 ),
-                            )))
-                            }
+                            )));
                         }
+                        self.export_presets()
                     }
                     toolbar::Message::AddTemplate => {
                         Task::done(Message::ToolBar(toolbar::Message::Upload(
@@ -754,19 +1293,62 @@ impl Editing {
                             get_templates_path(),
                         )))
                     }
+                    toolbar::Message::ToggleExportOnSave => {
+                        if self.project.pdf.export_on_save {
+                            self.project.pdf.export_on_save = false;
+                            return self.save_export_on_save();
+                        }
+                        //the destination is only stored once the user chose it
+                        let output = self
+                            .project
+                            .pdf
+                            .output
+                            .as_ref()
+                            .map(|output| self.current_dir.join(output));
+                        let Some(path) =
+                            output.or_else(|| save_file_dialog("pdf", &["pdf"]))
+                        else {
+                            return Task::none(); //abort
+                        };
+                        let path = path.with_extension("pdf");
+                        self.project.pdf.export_on_save = true;
+                        self.project.pdf.output = Some(self.relative_output(&path));
+                        let options = self.export_on_save_options();
+                        self.last_pdf_export = Some((path.clone(), options.clone()));
+                        Task::batch([
+                            self.save_export_on_save(),
+                            Task::perform(
+                                export_pdf(self.typst.clone(), path, options),
+                                Message::ExportedOnSave,
+                            ),
+                        ])
+                    }
                     toolbar::Message::ProjectExported(result) => match result {
-                        //TODO: Status bar
-                        Ok(_path) => {
+                        Ok(path) => {
                             println!("Project exported!");
+                            self.export_status = Some(ExportStatus::new(Ok(path)));
                             Task::none()
                         }
-                        Err(err) => Task::done(Message::PopUp(
-                            pop_up::Message::ShowPopUp(PopUpElement::new(
-                                PopUpType::Error,
-                                String::from("Project not exported!"),
-                                err.to_string(),
-                            )),
-                        )),
+                        Err(err) => {
+                            self.export_status =
+                                Some(ExportStatus::new(Err(err.to_string())));
+                            let pop_up = Task::done(Message::PopUp(
+                                pop_up::Message::ShowPopUp(PopUpElement::new(
+                                    PopUpType::Error,
+                                    String::from("Project not exported!"),
+                                    err.to_string(),
+                                )),
+                            ));
+                            match err {
+                                //locate the errors in the debug zone
+                                ExportError::CompilationError(diagnostics)
+                                | ExportError::PdfConformanceError(diagnostics) => {
+                                    Task::done(Message::ShowErrors(diagnostics))
+                                        .chain(pop_up)
+                                }
+                                _ => pop_up,
+                            }
+                        }
                     },
                     toolbar::Message::Universe => {
                         open_url("https://typst.app/universe/");
@@ -780,6 +1362,10 @@ impl Editing {
                         self.file_modal.show();
                         Task::none()
                     }
+                    toolbar::Message::ShowFonts => {
+                        self.font_manager.show(&self.typst);
+                        Task::none()
+                    }
                     toolbar::Message::NewProject => {
                         self.project_modal.show();
                         Task::none()
@@ -900,9 +1486,113 @@ impl Editing {
                 Task::none()
             }
             Message::FileModal(message) => self.file_modal.update(message),
+            Message::ProjectSettings(message) => self.project_settings.update(message),
+            Message::ExportOptions(message) => self.export_options.update(message),
+            Message::ExportPdf(options) => {
+                let path = save_file_dialog("pdf", &["pdf"]);
+                if let Some(export_path) = path {
+                    self.export_pdf_to(export_path, options)
+                } else {
+                    Task::none() //abort or error
+                }
+            }
+            Message::ExportedOnSave(result) => {
+                if let Err(err) = &result {
+                    eprintln!("Export on save failed: {err}");
+                }
+                let result = result.map_err(|err| err.to_string());
+                self.export_status = Some(ExportStatus::new(result));
+                Task::none()
+            }
+            Message::HtmlExported(result) => match result {
+                Ok((path, warnings)) => {
+                    let exported = Task::done(Message::ToolBar(
+                        toolbar::Message::ProjectExported(Ok(path)),
+                    ));
+                    if warnings.is_empty() {
+                        exported
+                    } else {
+                        Task::done(Message::ShowErrors(warnings)).chain(exported)
+                    }
+                }
+                Err(err) => Task::done(Message::ToolBar(
+                    toolbar::Message::ProjectExported(Err(err)),
+                )),
+            },
+            Message::ExportSvg(options) => {
+                let path = save_file_dialog("svg", &["svg"]);
+                if let Some(export_path) = path {
+                    Task::perform(
+                        export_svg(self.typst.clone(), export_path, options),
+                        |result| Message::ToolBar(toolbar::Message::ProjectExported(result)),
+                    )
+                } else {
+                    Task::none() //abort or error
+                }
+            }
+            Message::PresetsExported(results) => {
+                let failed = results.iter().filter(|(_, result)| result.is_err()).count();
+                let details = results
+                    .iter()
+                    .map(|(name, result)| match result {
+                        Ok(path) => format!("{name}: {}", path.display()),
+                        Err(err) => format!("{name}: {err}"),
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                let (pop_type, title) = if failed == 0 {
+                    (PopUpType::Info, String::from("All presets exported"))
+                } else {
+                    (
+                        PopUpType::Error,
+                        format!("{failed} of {} presets not exported!", results.len()),
+                    )
+                };
+                Task::done(Message::PopUp(pop_up::Message::ShowPopUp(PopUpElement::new(
+                    pop_type, title, details,
+                ))))
+            }
+            Message::ExportPng(options) => {
+                let path = save_file_dialog("png", &["png"]);
+                if let Some(export_path) = path {
+                    Task::perform(
+                        export_png(self.typst.clone(), export_path, options),
+                        |result| Message::ToolBar(toolbar::Message::ProjectExported(result)),
+                    )
+                } else {
+                    Task::none() //abort or error
+                }
+            }
+            Message::ProjectConfigured(config) => {
+                self.typst.configure(&config);
+                let saved = config.save(&self.current_dir);
+                self.project = *config;
+                match saved {
+                    Ok(_) => self.refresh_preview(),
+                    Err(err) => Task::done(Message::PopUp(pop_up::Message::ShowPopUp(
+                        PopUpElement::new(
+                            PopUpType::Error,
+                            String::from("Project settings not saved!"),
+                            err.to_string(),
+                        ),
+                    ))),
+                }
+            }
+            Message::FontManager(message) => {
+                self.font_manager.update(message, &self.typst)
+            }
             Message::ProjectModal(message) => {
                 self.project_modal.update(message).map(Message::ToolBar)
             }
+            Message::TemplateGallery(message) => {
+                if let template_gallery::Message::Use(template) = &message {
+                    self.project_modal.require_template(template.to_path_buf());
+                    self.project_modal.show();
+                }
+                self.template_gallery
+                    .update(message)
+                    .map(Message::TemplateGallery)
+            }
             Message::CreateFile(path) => {
                 self.create_file(path);
                 Task::none()
@@ -911,7 +1601,14 @@ impl Editing {
     }
 }
 
-fn view_errors<'a>(errors: &EcoVec<SourceDiagnostic>) -> Element<'a, Message> {
+/// Builds and returns the view of the diagnostics area, listing errors and warnings.
+///
+/// Each diagnostic shows its severity, location and message, followed by its hints.
+/// Warnings are styled differently from errors.
+fn view_errors<'a>(
+    errors: &EcoVec<SourceDiagnostic>,
+    world: &TideWorld,
+) -> Element<'a, Message> {
     container(column![
         row![
             space().width(Length::Fill),
@@ -920,17 +1617,23 @@ fn view_errors<'a>(errors: &EcoVec<SourceDiagnostic>) -> Element<'a, Message> {
                 .style(cancel_button),
         ],
         Scrollable::new(column(errors.iter().map(|diagnostic| {
+            let (severity, style) = match diagnostic.severity {
+                Severity::Error => ("error", error_text_style as fn(&Theme) -> _),
+                Severity::Warning => ("warning", warning_text_style as fn(&Theme) -> _),
+            };
             let mut error_message = column![];
-            if let Some(span_id) = diagnostic.span.id() {
-                error_message = error_message
-                    .push(text(span_id.vpath().as_rootless_path().to_string_lossy()));
-            }
-            error_message = error_message.push(text(diagnostic.message.to_string()));
+            let header = match world.location(diagnostic.span) {
+                Some(location) => format!("{severity}: {location}"),
+                None => severity.to_string(),
+            };
+            error_message = error_message.push(text(header).style(style));
+            error_message =
+                error_message.push(text(diagnostic.message.to_string()).style(style));
             error_message = error_message.push(column(
                 diagnostic
                     .hints
                     .iter()
-                    .map(|hint| text(hint.to_string()).into()),
+                    .map(|hint| text(format!("hint: {hint}")).style(style).into()),
             ));
             error_message.into()
         })))
@@ -947,11 +1650,15 @@ fn view_errors<'a>(errors: &EcoVec<SourceDiagnostic>) -> Element<'a, Message> {
 /// The status bar displays:
 /// - the current cursor position as line and column numbers ;
 /// - the name of the currently opened file ;
+/// - the time and result of the last export, if any ;
+/// - the number of warnings of the last compilation, if any ;
 /// - a flag indicating whether the file has been saved.
 fn view_status_bar<'a>(
     cursor_pos: text_editor::Position,
     current_file: String,
     saved: bool,
+    export_status: Option<String>,
+    warnings: usize,
 ) -> Element<'a, Message> {
     const SPACING: f32 = 20.0;
 
@@ -960,6 +1667,12 @@ fn view_status_bar<'a>(
         text(format! {"{}:{}", cursor_pos.line, cursor_pos.column}),
         text(current_file),
         space().width(Length::Fill),
+        text(export_status.unwrap_or_default()),
+        match warnings {
+            0 => text(""),
+            1 => text("1 warning").style(warning_text_style),
+            _ => text(format!("{warnings} warnings")).style(warning_text_style),
+        },
         text(format! {"saved: {}", saved}),
         space().width(SPACING)
     ]
@@ -989,10 +1702,55 @@ fn view_status_bar<'a>(
 //     .into()
 // }
 
-/// Initializes the [`TideWorld`] with a temporary, fake, main file.
-fn init_world() -> TideWorld {
+/// Initializes the [`TideWorld`] rooted at `root` with a temporary, fake, main file.
+///
+/// Packages are resolved from the standard Typst directories and the configured package path.
+pub fn init_world(
+    config: &TypstConfig,
+    root: &Path,
+) -> TideWorld {
     let main_id = FileId::new_fake(VirtualPath::new("main.typ"));
-    TideWorld::new(main_id, None)
+    let packages = PackageStorage::new(config.package_path.clone(), None);
+    TideWorld::new(root.to_path_buf(), main_id, None, packages)
+}
+
+/// Time and result of an export, shown in the status bar.
+struct ExportStatus {
+    /// Local time at which the export ended.
+    time: DateTime<Local>,
+    /// Path of the exported file, or the error message.
+    result: Result<PathBuf, String>,
+}
+
+impl ExportStatus {
+    /// Creates a new [`ExportStatus`] for an export which just ended.
+    fn new(result: Result<PathBuf, String>) -> Self {
+        Self {
+            time: Local::now(),
+            result,
+        }
+    }
+}
+
+impl Display for ExportStatus {
+    fn fmt(
+        &self,
+        f: &mut Formatter<'_>,
+    ) -> std::fmt::Result {
+        let time = self.time.format("%H:%M:%S");
+        match &self.result {
+            Ok(path) => {
+                let name = path.file_name().unwrap_or(path.as_os_str());
+                write!(f, "exported {} at {time}", name.to_string_lossy())
+            }
+            //only the first line of multi-line errors fits in the status bar
+            Err(err) => write!(
+                f,
+                "export failed at {time}: {}",
+                err.lines().next().unwrap_or_default()
+            ),
+        }
+    }
 }
 
 /// Tracks the currently selected buffer and file.
@@ -1136,10 +1894,15 @@ pub enum Message {
     ResizeTree(f32),
     /// Resizes the preview area to the given width.
     ResizePreview(f32),
-    /// Result of an SVG export operation for the current document.
-    SvgGenerated(Result<Vec<String>, ExportError>),
-    /// Loaded preview images (SVG handles) to be rendered on screen.
-    PreviewLoaded(Vec<Handle>),
+    /// Result of the compilation of the document to preview with the given number,
+    /// with its warnings.
+    PreviewCompiled(u64, Box<Result<Warned<PagedDocument>, ExportError>>),
+    /// No edit was made since the edit with the given number, for the live preview.
+    LivePreview(u64),
+    /// A message emitted by the preview (e.g. a page rendered).
+    Preview(preview::Message),
+    /// Opens the file with the given id and moves the cursor to the given byte offset.
+    JumpToSource(FileId, usize),
     /// Triggers the autocompletion logic based on current cursor position.
     Autocomplete,
     /// Displays the autocompletion menu with the given suggestions.
@@ -1160,6 +1923,34 @@ pub enum Message {
     FileModal(modal::Message),
     /// A message emitted by the "new project" modal.
     ProjectModal(modal::Message),
+    /// Forwarded message from the template gallery.
+    TemplateGallery(template_gallery::Message),
+    /// A message emitted by the font manager panel.
+    FontManager(font_manager::Message),
+    /// A message emitted by the project settings modal.
+    ProjectSettings(project_settings::Message),
+    /// The project configuration was edited and must be applied and saved.
+    ProjectConfigured(Box<ProjectConfig>),
+    /// A message emitted by the export options modal.
+    ExportOptions(export_options::Message),
+    /// Exports the project as a PDF with the given options.
+    ExportPdf(PdfExportOptions),
+    /// Result of an export following a compilation, only reported in the status bar.
+    ExportedOnSave(Result<PathBuf, ExportError>),
+    /// Result of an HTML export, with the warnings of the compilation (e.g. unsupported elements).
+    HtmlExported(Result<(PathBuf, EcoVec<SourceDiagnostic>), ExportError>),
+    /// Exports the project as PNG images with the given options.
+    ExportPng(PngExportOptions),
+    /// Exports the project as SVG with the given options.
+    ExportSvg(SvgExportOptions),
+    /// Results of the export presets of the project, identified by their names.
+    PresetsExported(Vec<(String, Result<PathBuf, ExportError>)>),
+    /// Files of the project changed on disk, outside of Tide.
+    FilesChanged(Changes),
+    /// Fonts changed in a watched font directory.
+    FontsChanged,
+    /// No font changed since the change with the given number.
+    FontsSettled(u64),
 
     ShowErrors(EcoVec<SourceDiagnostic>),
     HideErrors,
@@ -1168,7 +1959,8 @@ pub enum Message {
 #[cfg(test)]
 mod test {
     use super::*;
-    use typst::syntax::Source;
+    use crate::data::config::project::PROJECT_CONFIG_FILE_NAME;
+    use typst::syntax::{Source, Span};
 
     const MAIN_FILE_NAME: &str = "main.typ";
     const OTHER_FILE_NAME: &str = "other.typ";
@@ -1178,7 +1970,7 @@ mod test {
     fn create_editing() -> Editing {
         let path = PathBuf::from(TEST_PROJECT_ROOT);
         let config = EditorConfig::default();
-        Editing::new(config, path)
+        Editing::new(config, TypstConfig::default(), path)
     }
 
     fn create_file_id() -> FileId {
@@ -1204,6 +1996,34 @@ mod test {
         assert!(editing.buffers.is_empty())
     }
 
+    #[test]
+    fn test_close_dirty_buffer() {
+        let root = std::env::temp_dir().join("tide-test-close-dirty-buffer");
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).unwrap();
+        fs::write(root.join(MAIN_FILE_NAME), "= Main").unwrap();
+        let mut editing =
+            Editing::new(EditorConfig::default(), TypstConfig::default(), root.clone());
+        let file_id = FileId::new(None, VirtualPath::new(MAIN_FILE_NAME));
+        editing.typst.change_main(file_id);
+        let mut buffer = Buffer::from_path(&root.join(MAIN_FILE_NAME)).unwrap();
+        buffer.content = text_editor::Content::with_text("#panic(\"unsaved\")");
+        buffer.is_saved = false;
+        editing.buffers.insert(file_id, buffer.clone());
+        editing.update_source(file_id, buffer);
+        assert!(typst::compile::<PagedDocument>(&editing.typst).output.is_err());
+
+        editing.close_buffer(file_id);
+        fs::write(root.join(MAIN_FILE_NAME), "= Changed").unwrap();
+        let _task = editing.update(Message::FilesChanged(Changes {
+            paths: vec![root.join(MAIN_FILE_NAME)],
+            entries_changed: false,
+        }));
+        assert!(typst::compile::<PagedDocument>(&editing.typst).output.is_ok());
+        assert_eq!(editing.typst.source(file_id).unwrap().text(), "= Changed");
+        let _ = fs::remove_dir_all(root);
+    }
+
     // #[test]
     // fn test_delete_file() {
     //     let text = "Hello, World!";
@@ -1242,7 +2062,7 @@ mod test {
         let mut editing = create_editing();
         let _task =
             editing.update(Message::ActionPerformed(Action::Edit(Edit::Insert('a'))));
-        assert_eq!(editing.current.buffer.content.text(), "a\n");
+        assert_eq!(editing.current.buffer.content.text(), "a");
     }
 
     #[test]
@@ -1263,6 +2083,86 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_jump_to_source() {
+        let root = std::env::temp_dir().join("tide-test-jump-to-source");
+        fs::create_dir_all(&root).unwrap();
+        fs::write(root.join(MAIN_FILE_NAME), "= Main").unwrap();
+        fs::write(root.join(OTHER_FILE_NAME), "first line\nsécond line").unwrap();
+        let mut editing =
+            Editing::new(EditorConfig::default(), TypstConfig::default(), root.clone());
+        let file_id = FileId::new(None, VirtualPath::new(OTHER_FILE_NAME));
+
+        //the byte offset of "line" on the second line, after a two-byte character
+        let _task = editing.update(Message::JumpToSource(file_id, 19));
+        assert_eq!(editing.current_file_id(), Some(file_id));
+        editing
+            .current
+            .buffer
+            .content
+            .perform(Action::Edit(Edit::Insert('|')));
+        assert_eq!(
+            editing.current.buffer.content.line(1).unwrap().text,
+            "sécond |line"
+        );
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn test_discard_outdated_preview() {
+        let mut editing = create_editing();
+        let _task = editing.update(Message::ToolBar(toolbar::Message::ForcePreview));
+        let _task = editing.update(Message::ToolBar(toolbar::Message::ForcePreview));
+        let failed = || {
+            let warning = SourceDiagnostic::warning(Span::detached(), "outdated");
+            Box::new(Err(ExportError::CompilationError(EcoVec::from([warning]))))
+        };
+
+        let _task = editing.update(Message::PreviewCompiled(1, failed()));
+        assert!(editing.warnings.is_empty());
+        let _task = editing.update(Message::PreviewCompiled(2, failed()));
+        assert_eq!(editing.warnings.len(), 1);
+    }
+
+    #[test]
+    fn test_export_on_save_timestamp() {
+        let mut editing = create_editing();
+        let options = PdfExportOptions {
+            timestamp: Some(DateTime::UNIX_EPOCH),
+            ..PdfExportOptions::default()
+        };
+        editing.last_pdf_export = Some((PathBuf::from("main.pdf"), options));
+        let options = editing.export_on_save_options();
+        assert!(options.timestamp > Some(DateTime::UNIX_EPOCH));
+    }
+
+    #[test]
+    fn test_export_keeps_project_config() {
+        let root = std::env::temp_dir().join("tide-test-export-keeps-project-config");
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).unwrap();
+        let content = "# shared\n[pdf]\nexport-on-save = true\noutput = \"main.pdf\"\n";
+        fs::write(root.join(PROJECT_CONFIG_FILE_NAME), content).unwrap();
+        let mut editing =
+            Editing::new(EditorConfig::default(), TypstConfig::default(), root.clone());
+
+        let _task = editing.export_pdf_to(root.join("out"), PdfExportOptions::default());
+        assert_eq!(
+            fs::read_to_string(root.join(PROJECT_CONFIG_FILE_NAME)).unwrap(),
+            content
+        );
+        assert_eq!(editing.pdf_output(), Some(root.join("out.pdf")));
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn test_live_preview_without_preview() {
+        let mut editing = create_editing();
+        editing.edits = 1;
+        let _task = editing.update(Message::LivePreview(1));
+        assert_eq!(editing.compilations, 0);
+    }
+
     #[test]
     fn test_modal() {
         let mut editing = create_editing();
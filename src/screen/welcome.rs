@@ -1,18 +1,16 @@
 use super::component::toolbar::{self, open_url, welcome_toolbar};
 use crate::data::style::button::{files_button, toolbar_button};
-use crate::file_manager::file::{
-    get_recent_paths, get_templates_path, load_file_dialog, ProjectCache,
-};
-use crate::file_manager::import::TEMPLATE;
+use crate::data::config::appearance::TypstConfig;
+use crate::file_manager::file::{get_recent_paths, ProjectCache};
 use crate::screen::component::modal;
 use crate::screen::component::modal::ProjectModal;
+use crate::screen::component::template_gallery::{self, TemplateGallery};
 use iced::widget::{button, row, space, stack, svg, text};
 use iced::Length::Fill;
 use iced::{
     widget::{column, container},
     Alignment, Element, Length, Task,
 };
-use std::path::PathBuf;
 use std::sync::LazyLock;
 
 const WELCOME_BUTTON_PADDING: f32 = 2.0;
@@ -36,6 +34,8 @@ pub struct Welcome {
     recent_files: Vec<ProjectCache>,
     /// The modal used for creating a new project.
     project_modal: ProjectModal,
+    /// The panel listing the local templates.
+    template_gallery: TemplateGallery,
 }
 
 /// Messages handled by the _Welcome_ view.
@@ -45,14 +45,19 @@ pub enum Message {
     ToolBar(toolbar::Message),
     /// Forwarded message from the project creation modal.
     ProjectModal(modal::Message),
+    /// Forwarded message from the template gallery.
+    TemplateGallery(template_gallery::Message),
 }
 
 impl Welcome {
     /// Creates a new [`Welcome`] view with cached recent projects.
-    pub fn new() -> Self {
+    ///
+    /// The Typst configuration is used to render the thumbnails of the templates.
+    pub fn new(typst_config: TypstConfig) -> Self {
         Self {
             recent_files: get_recent_paths(),
             project_modal: ProjectModal::new(),
+            template_gallery: TemplateGallery::new(typst_config),
         }
     }
 
@@ -66,19 +71,10 @@ impl Welcome {
     ) -> Task<Message> {
         match message {
             Message::ToolBar(message) => match message {
-                toolbar::Message::StartFromTemplate => {
-                    if let Some(template) = load_file_dialog(
-                        &get_templates_path().unwrap_or(PathBuf::from("/")),
-                        "Typst template",
-                        &TEMPLATE,
-                    ) {
-                        self.project_modal.require_template(template);
-                        return Task::done(Message::ToolBar(
-                            toolbar::Message::NewProject,
-                        ));
-                    }
-                    Task::none()
-                }
+                toolbar::Message::StartFromTemplate => self
+                    .template_gallery
+                    .show(None)
+                    .map(Message::TemplateGallery),
                 toolbar::Message::NewProject => {
                     self.project_modal.show();
                     Task::none()
@@ -96,6 +92,15 @@ impl Welcome {
             Message::ProjectModal(message) => {
                 self.project_modal.update(message).map(Message::ToolBar)
             }
+            Message::TemplateGallery(message) => {
+                if let template_gallery::Message::Use(template) = &message {
+                    self.project_modal.require_template(template.to_path_buf());
+                    self.project_modal.show();
+                }
+                self.template_gallery
+                    .update(message)
+                    .map(Message::TemplateGallery)
+            }
         }
     }
 
@@ -196,6 +201,14 @@ impl Welcome {
                 .into();
         }
 
+        if self.template_gallery.visible {
+            return stack![
+                screen,
+                self.template_gallery.view().map(Message::TemplateGallery)
+            ]
+            .into();
+        }
+
         screen.into()
     }
 }
@@ -1,120 +1,136 @@
 //this file should be moved to a dedicated module
 
+use crate::data::config::project::ProjectConfig;
 use crate::file_manager::file::{get_fonts_path, get_relative_path};
 use crate::file_manager::import::load::ImportedFile;
-use chrono::{Datelike, FixedOffset, Local, Utc};
+use chrono::{DateTime, Datelike, FixedOffset, Local, Utc};
 use iced::widget::text_editor::Content;
 use std::collections::HashMap;
 use std::fmt::{Debug, Formatter};
 use std::fs;
-use std::path::PathBuf;
-use typst::diag::{FileError, FileResult};
-use typst::foundations::{Bytes, Datetime};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+use typst::diag::{FileError, FileResult, SourceDiagnostic};
+use typst::ecow::EcoVec;
+use typst::foundations::{Bytes, Datetime, Dict, IntoValue};
 use typst::layout::Abs;
-use typst::syntax::{FileId, Source, VirtualPath};
-use typst::text::{Font, FontBook, TextElem, TextSize};
+use typst::syntax::{FileId, Source, Span, VirtualPath};
+use typst::text::{Font, FontBook, FontFamily as TypstFontFamily, FontList, Lang, TextElem, TextSize};
 use typst::utils::LazyHash;
-use typst::{Library, World, LibraryExt};
+use typst::{Feature, Library, LibraryExt, World, WorldExt};
 use typst_ide::IdeWorld;
 
-const LIBERTINUS_SERIF_REGULAR: &[u8] =
-    include_bytes!("../assets/fonts/libertinus/LibertinusSerif-Regular.ttf");
-const LIBERTINUS_SERIF_BOLD: &[u8] =
-    include_bytes!("../assets/fonts/libertinus/LibertinusSerif-Bold.ttf");
-const LIBERTINUS_SERIF_ITALIC: &[u8] =
-    include_bytes!("../assets/fonts/libertinus/LibertinusSerif-Italic.ttf");
-const LIBERTINUS_SERIF_BOLD_ITALIC: &[u8] =
-    include_bytes!("../assets/fonts/libertinus/LibertinusSerif-BoldItalic.ttf");
-const LIBERTINUS_SERIF_SEMIBOLD: &[u8] =
-    include_bytes!("../assets/fonts/libertinus/LibertinusSerif-Semibold.ttf");
-const LIBERTINUS_SERIF_MATH: &[u8] =
-    include_bytes!("../assets/fonts/libertinus/LibertinusMath-Regular.ttf");
-const LIBERTINUS_SERIF_INITIALS: &[u8] =
-    include_bytes!("../assets/fonts/libertinus/LibertinusSerifInitials-Regular.ttf");
-const NEW_CMM_MATH_REGULAR: &[u8] =
-    include_bytes!("../assets/fonts/NewCMMath-Regular.otf");
+use fonts::{families, FontFamily, FontSearcher, FontSlot, FontSource, PROJECT_FONTS_DIR};
+use package::{decode_utf8, PackageStorage};
+
+pub mod fonts;
+pub mod package;
 
 /// The typesetting environment for the Tide IDE, implementing the Typst [`World`] trait.
 ///
 /// Provides cached access to files, fonts, and Typst sources required for typesetting.
+/// Project files are read from disk the first time they are requested, unless an open
+/// editor buffer overrides them.
 pub struct TideWorld {
+    /// Absolute path of the project root, against which project files are resolved.
+    root: PathBuf,
     /// Identifier of the main source file currently being typeset.
     main: FileId,
     /// Lazily-initialized standard Typst library.
     library: LazyHash<Library>,
-    /// Font metadata registry generated from loaded fonts.
+    /// Standard library with the experimental HTML feature, for the HTML export.
+    html_library: LazyHash<Library>,
+    /// Font metadata registry generated from discovered fonts.
     book: LazyHash<FontBook>,
-    /// Loaded font instances used for rendering.
-    fonts: Vec<Font>,
+    /// Discovered fonts, in the order of the font book, loaded on first use.
+    fonts: Arc<Vec<FontSlot>>,
     /// File storage for both assets and Typst sources.
     files: Files,
+    /// On-disk storage used to resolve `@preview` and `@local` package imports.
+    packages: PackageStorage,
+    /// Fixed time of the compilations, `None` to use the wall clock.
+    now: Option<DateTime<Utc>>,
+    /// Warnings about the invalid settings of the project.
+    ///
+    /// See [`ProjectConfig::warnings`].
+    config_warnings: EcoVec<SourceDiagnostic>,
 }
 
-/// Loads all font files from the given directory and parses their faces.
+/// Embedded, system and user fonts, discovered by the first world and shared by the next
+/// ones, so that scanning the font directories happens once and not for every world.
+static SHARED_FONTS: Mutex<Option<Vec<FontSlot>>> = Mutex::new(None);
+
+/// Returns the embedded, system and user fonts, as well as the fonts of the project.
 ///
-/// Returns a list of valid [`Font`] instances found in the folder.
-fn load_fonts(font_path: PathBuf) -> Vec<Font> {
-    let mut fonts = Vec::new();
-
-    if let Ok(paths) = fs::read_dir(font_path) {
-        for path in paths {
-            let font = &path.expect("Can't read this path").path();
-            println!("font found: {}", font.display());
-            if let Ok(font_data) = fs::read(font) {
-                let font_bytes = Bytes::new(font_data);
-                let loaded_fonts: Vec<_> = Font::iter(font_bytes).collect(); //if the font data contain several faces
-                fonts.extend(loaded_fonts);
+/// The fonts shared by every world are discovered again only if `rescan` is set
+/// or if no world has discovered them yet. Font data is not loaded here but on first use.
+fn search_fonts(
+    root: &Path,
+    rescan: bool,
+) -> (FontBook, Vec<FontSlot>) {
+    let fonts = {
+        let mut shared = SHARED_FONTS.lock().unwrap();
+        match &*shared {
+            Some(fonts) if !rescan => fonts.clone(),
+            _ => {
+                let mut searcher = FontSearcher::new();
+                searcher.search_system();
+                if let Some(user_fonts_path) = get_fonts_path() {
+                    searcher.search_dir(&user_fonts_path, FontSource::User);
+                }
+                let (_, fonts) = searcher.finish();
+                shared.insert(fonts).clone()
             }
         }
-    }
-
-    fonts
+    };
+    let mut searcher = FontSearcher::from_slots(fonts);
+    search_project_fonts(&mut searcher, root);
+    searcher.finish()
 }
 
-/// Retrieves the default "Libertinus" font and returns it as a collection of OpenType fonts.
-fn default_fonts() -> Vec<Font> {
-    let mut fonts = Vec::new();
-    fonts.extend(Font::iter(Bytes::new(LIBERTINUS_SERIF_REGULAR)));
-    fonts.extend(Font::iter(Bytes::new(LIBERTINUS_SERIF_BOLD)));
-    fonts.extend(Font::iter(Bytes::new(LIBERTINUS_SERIF_ITALIC)));
-    fonts.extend(Font::iter(Bytes::new(LIBERTINUS_SERIF_BOLD_ITALIC)));
-    fonts.extend(Font::iter(Bytes::new(LIBERTINUS_SERIF_SEMIBOLD)));
-    fonts.extend(Font::iter(Bytes::new(LIBERTINUS_SERIF_MATH)));
-    fonts.extend(Font::iter(Bytes::new(LIBERTINUS_SERIF_INITIALS)));
-    fonts.extend(Font::iter(Bytes::new(NEW_CMM_MATH_REGULAR)));
-
-    fonts
+/// Discovers the fonts in the `fonts` directory of the project, if it exists.
+fn search_project_fonts(
+    searcher: &mut FontSearcher,
+    root: &Path,
+) {
+    let project_fonts_path = root.join(PROJECT_FONTS_DIR);
+    if project_fonts_path.is_dir() {
+        searcher.search_dir(&project_fonts_path, FontSource::Project);
+    }
 }
 
 impl TideWorld {
-    /// Constructs a new [`TideWorld`] instance with an optional asset map.
+    /// Constructs a new [`TideWorld`] instance rooted at `root` with an optional asset map.
     ///
-    /// Initializes the standard library, loads fonts from disk,
-    /// and sets up the font book and file storage.
+    /// Initializes the standard library, discovers the fonts of the project
+    /// (the other fonts are discovered once for every world, see [`SHARED_FONTS`]),
+    /// and sets up the font book, file storage and package storage.
+    /// Project files are not read here but on demand.
     pub fn new(
+        root: PathBuf,
         main: FileId,
         assets: Option<HashMap<FileId, Bytes>>,
+        packages: PackageStorage,
     ) -> Self {
-        let library = LazyHash::new(library());
-
-        let mut fonts: Vec<Font> = default_fonts();
-
-        if let Some(user_fonts_path) = get_fonts_path() {
-            let user_fonts = load_fonts(user_fonts_path);
-            fonts.extend(user_fonts);
-        }
-
-        let book = LazyHash::new(FontBook::from_fonts(&fonts));
+        let config = ProjectConfig::default();
+        let (book, fonts) = search_fonts(&root, false);
 
         TideWorld {
+            root,
             main,
-            library,
-            book,
-            fonts,
+            library: LazyHash::new(library(&config, false)),
+            html_library: LazyHash::new(library(&config, true)),
+            book: LazyHash::new(book),
+            fonts: Arc::new(fonts),
             files: Files {
                 assets: assets.unwrap_or_default(),
                 sources: Default::default(),
+                slots: Default::default(),
             },
+            packages,
+            now: None,
+            config_warnings: EcoVec::new(),
         }
     }
 
@@ -129,14 +145,134 @@ impl TideWorld {
         Some(FileId::new(None, VirtualPath::new(relative_path)))
     }
 
-    /// Replaces the source content for an existing file in the world.
+    /// Changes the project root and drops every file read from the previous one.
+    ///
+    /// The fonts of the previous project are replaced by the fonts of the new one.
+    pub fn change_root(
+        &mut self,
+        root: PathBuf,
+    ) {
+        self.root = root;
+        self.files.assets.clear();
+        self.files.sources.clear();
+        self.files.slots = Default::default();
+
+        //only the fonts of the project depend on the root
+        let fonts = self
+            .fonts
+            .iter()
+            .filter(|slot| slot.source() != FontSource::Project)
+            .cloned()
+            .collect();
+        let mut searcher = FontSearcher::from_slots(fonts);
+        search_project_fonts(&mut searcher, &self.root);
+        let (book, fonts) = searcher.finish();
+        self.book = LazyHash::new(book);
+        self.fonts = Arc::new(fonts);
+    }
+
+    /// Rebuilds the standard library with the inputs and default styles of the project,
+    /// and fixes the time of the compilations if the project is reproducible.
+    ///
+    /// The invalid settings of the project are ignored and become the configuration
+    /// warnings of the world, see [`TideWorld::config_warnings`].
+    pub fn configure(
+        &mut self,
+        config: &ProjectConfig,
+    ) {
+        self.library = LazyHash::new(library(config, false));
+        self.html_library = LazyHash::new(library(config, true));
+        self.now = config.build.timestamp();
+        self.config_warnings = config
+            .warnings()
+            .into_iter()
+            .map(|message| SourceDiagnostic::warning(Span::detached(), message))
+            .collect();
+    }
+
+    /// Returns the warnings about the invalid settings of the project, without location,
+    /// to be reported with the diagnostics of the compilations.
+    pub fn config_warnings(&self) -> &EcoVec<SourceDiagnostic> {
+        &self.config_warnings
+    }
+
+    /// Returns a copy of the world compiling with the experimental HTML feature,
+    /// for the HTML export.
+    pub fn for_html(&self) -> Self {
+        Self {
+            library: self.html_library.clone(),
+            ..self.clone()
+        }
+    }
+
+    /// Returns the absolute path of the project root.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Returns the fixed time of the compilations, or `None` if the wall clock is used.
+    pub fn timestamp(&self) -> Option<DateTime<Utc>> {
+        self.now
+    }
+
+    /// Returns the `file:line:column` location of a span, if it points into a file.
+    ///
+    /// Lines and columns are one-based. Files of packages are prefixed
+    /// with their package specification.
+    pub fn location(
+        &self,
+        span: Span,
+    ) -> Option<String> {
+        let id = span.id()?;
+        let source = self.source(id).ok()?;
+        let range = self.range(span)?;
+        let (line, column) = source.lines().byte_to_line_column(range.start)?;
+        let path = id.vpath().as_rootless_path();
+        let file = match id.package() {
+            Some(package) => format!("{package}/{}", path.display()),
+            None => path.display().to_string(),
+        };
+        Some(format!("{file}:{}:{}", line + 1, column + 1))
+    }
+
+    /// Returns the font families known to the font book, with their faces.
+    pub fn font_families(&self) -> Vec<FontFamily> {
+        families(&self.fonts)
+    }
+
+    /// Discovers the available fonts again and rebuilds the font book.
+    ///
+    /// Fonts added to or removed from the font directories since the creation
+    /// of the world become available to (or disappear from) the next compilations.
+    pub fn reload_fonts(&mut self) {
+        let (book, fonts) = search_fonts(&self.root, true);
+        self.book = LazyHash::new(book);
+        self.fonts = Arc::new(fonts);
+    }
+
+    /// Overrides the source content of a file with the content of an editor buffer.
+    ///
+    /// The buffer takes precedence over the disk content until the file is removed.
     pub fn reload_source_from_content(
         &mut self,
         id: FileId,
         content: &Content,
     ) {
-        if let Some(source) = self.files.sources.get_mut(&id) {
-            source.replace(content.text().as_str());
+        let text = content.text();
+        match self.files.sources.get_mut(&id) {
+            Some(source) => {
+                source.replace(text.as_str());
+            }
+            None => {
+                let source = match self.read_source(id) {
+                    Ok(mut source) => {
+                        source.replace(text.as_str());
+                        source
+                    }
+                    Err(_) => Source::new(id, text),
+                };
+                self.files.sources.insert(id, source);
+            }
         }
     }
 
@@ -188,6 +324,75 @@ impl TideWorld {
     ) {
         self.files.assets.remove(&id);
         self.files.sources.remove(&id);
+        self.files.slots.lock().unwrap().remove(&id);
+    }
+
+    /// Drops the content of the closed editor buffer of a file, so that the next
+    /// compilations read the file from disk again.
+    pub fn close_source(
+        &mut self,
+        id: FileId,
+    ) {
+        self.files.sources.remove(&id);
+        self.files.slots.lock().unwrap().remove(&id);
+    }
+
+    /// Forgets what is known about the disk content of a file changed outside the editor.
+    ///
+    /// Sources coming from editor buffers are kept: they are replaced when the buffer
+    /// is reloaded, and dropped by [`TideWorld::close_source`] when it is closed.
+    pub fn invalidate(
+        &mut self,
+        id: FileId,
+    ) {
+        self.files.assets.remove(&id);
+        self.files.slots.lock().unwrap().remove(&id);
+    }
+
+    /// Resolves the path of a project file on disk.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`FileError::AccessDenied`] if the file lies outside the project root.
+    fn disk_path(
+        &self,
+        id: FileId,
+    ) -> FileResult<PathBuf> {
+        id.vpath()
+            .resolve(&self.root)
+            .ok_or(FileError::AccessDenied)
+    }
+
+    /// Reads a project file from disk, or from the cache if it didn't change since.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`FileError`] if the file can't be read.
+    fn read_file(
+        &self,
+        id: FileId,
+    ) -> FileResult<Bytes> {
+        let path = self.disk_path(id)?;
+        let mut slots = self.files.slots.lock().unwrap();
+        let slot = slots.entry(id).or_insert_with(FileSlot::new);
+        slot.refresh(&path);
+        slot.bytes.clone()
+    }
+
+    /// Reads and parses a project source from disk, or from the cache if it didn't change since.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`FileError`] if the file can't be read or is not valid UTF-8.
+    fn read_source(
+        &self,
+        id: FileId,
+    ) -> FileResult<Source> {
+        let path = self.disk_path(id)?;
+        let mut slots = self.files.slots.lock().unwrap();
+        let slot = slots.entry(id).or_insert_with(FileSlot::new);
+        slot.refresh(&path);
+        slot.source(id)
     }
 }
 
@@ -209,6 +414,8 @@ impl World for TideWorld {
 
     /// Retrieves a source file by ID, if available.
     ///
+    /// Files belonging to a package are resolved from the [`PackageStorage`].
+    /// Open editor buffers take precedence over the content on disk.
     /// Returns an error if the file is not found.
     ///
     /// See [`World::source`].
@@ -216,15 +423,19 @@ impl World for TideWorld {
         &self,
         id: FileId,
     ) -> FileResult<Source> {
-        if let Some(file) = self.files.sources.get(&id) {
+        if id.package().is_some() {
+            self.packages.source(id)
+        } else if let Some(file) = self.files.sources.get(&id) {
             Ok(file.clone())
         } else {
-            Err(FileError::NotFound(id.vpath().as_rootless_path().into()))
+            self.read_source(id)
         }
     }
 
     /// Retrieves a binary asset file by ID, if available.
     ///
+    /// Files belonging to a package are resolved from the [`PackageStorage`].
+    /// Files added in memory take precedence over the content on disk.
     /// Returns an error if the file is not found.
     ///
     /// See [`World::file`].
@@ -232,41 +443,45 @@ impl World for TideWorld {
         &self,
         id: FileId,
     ) -> FileResult<Bytes> {
-        println!(
-            "id: {:?}, found: {:?}",
-            id,
-            self.files.assets.contains_key(&id)
-        );
-        if let Some(file) = self.files.assets.get(&id).cloned() {
+        if id.package().is_some() {
+            self.packages.file(id)
+        } else if let Some(file) = self.files.assets.get(&id).cloned() {
             Ok(file)
+        } else if let Some(source) = self.files.sources.get(&id) {
+            Ok(Bytes::from_string(source.text().to_owned()))
         } else {
-            Err(FileError::NotFound(id.vpath().as_rootless_path().into()))
+            self.read_file(id)
         }
     }
 
-    /// Retrieves a font by index.
+    /// Retrieves a font by index, loading its data the first time it is requested.
     ///
-    /// Returns `None` if the index is out of bounds.
+    /// Returns `None` if the index is out of bounds or if the font can't be loaded.
     ///
     /// See [`World::font`].
     fn font(
         &self,
         index: usize,
     ) -> Option<Font> {
-        self.fonts.get(index).cloned()
+        self.fonts.get(index)?.get()
     }
 
-    /// Returns the current date/time. See [`World::today`].
+    /// Returns the current date/time, or the fixed one of a reproducible project.
+    /// See [`World::today`].
+    ///
+    /// Without an explicit offset, a fixed date is taken in UTC rather than in the
+    /// local timezone so that it doesn't depend on the machine.
     fn today(
         &self,
         offset: Option<i64>,
     ) -> Option<Datetime> {
-        let now = Utc::now();
+        let now = self.now.unwrap_or_else(Utc::now);
         let with_offset = match offset {
             Some(hours) => {
                 let seconds = i32::try_from(hours).ok()?.checked_mul(3600)?;
                 now.with_timezone(&FixedOffset::east_opt(seconds)?)
             }
+            None if self.now.is_some() => now.fixed_offset(),
             None => now.with_timezone(&Local).fixed_offset(),
         };
 
@@ -285,28 +500,58 @@ impl IdeWorld for TideWorld {
     }
 }
 
-/// Initializes the standard Typst library with default styles.
+/// Initializes the standard Typst library with the inputs and default styles of a project.
 ///
-/// Currently, sets the default text size.
-fn library() -> Library {
-    let mut lib = Library::default();
-    // lib.styles
-    //     .set(TextElem::set_size(TextSize(Abs::pt(11.0).into())));
-    //lib.styles.set(TextElem::set_font(FontList(...)));
+/// Inputs are exposed through `sys.inputs`. Invalid styles are ignored,
+/// see [`ProjectConfig::warnings`].
+/// The experimental HTML feature is enabled if `html` is `true`, for the HTML export.
+fn library(
+    config: &ProjectConfig,
+    html: bool,
+) -> Library {
+    let mut inputs = Dict::new();
+    for (key, value) in &config.inputs {
+        inputs.insert(key.as_str().into(), value.as_str().into_value());
+    }
+    let features = if html { vec![Feature::Html] } else { vec![] };
+    let mut lib = Library::builder()
+        .with_inputs(inputs)
+        .with_features(features.into_iter().collect())
+        .build();
+
+    let style = &config.style;
+    if let Some(font) = &style.font {
+        lib.styles
+            .set(TextElem::font, FontList(vec![TypstFontFamily::new(font)]));
+    }
+    if let Some(size) = style.size {
+        lib.styles.set(TextElem::size, TextSize(Abs::pt(size).into()));
+    }
+    if let Some(lang) = &style.lang {
+        if let Ok(lang) = lang.parse::<Lang>() {
+            lib.styles.set(TextElem::lang, lang);
+        }
+    }
     lib
 }
 
 impl Clone for TideWorld {
     fn clone(&self) -> TideWorld {
         Self {
+            root: self.root.clone(),
             main: self.main,
             library: self.library.clone(),
+            html_library: self.html_library.clone(),
             book: self.book.clone(),
             fonts: self.fonts.clone(),
             files: Files {
                 assets: self.files.assets.clone(),
                 sources: self.files.sources.clone(),
+                slots: self.files.slots.clone(),
             },
+            packages: self.packages.clone(),
+            now: self.now,
+            config_warnings: self.config_warnings.clone(),
         }
     }
 }
@@ -341,21 +586,94 @@ impl Debug for TideWorld {
 
 /// Internal file storage used by [`TideWorld`].
 ///
-/// Manages both assets (binary files) and Typst sources (text files).
+/// Manages both assets (binary files) and Typst sources (text files) held in memory,
+/// which override the files lazily read from disk.
 struct Files {
     /// Map of binary asset files, keyed by [`FileId`].
     assets: HashMap<FileId, Bytes>,
-    /// Map of Typst source files, keyed by [`FileId`].
+    /// Map of Typst source files (e.g. open editor buffers), keyed by [`FileId`].
     sources: HashMap<FileId, Source>,
+    /// Cache of the files read from disk, shared between clones of the world.
+    slots: Arc<Mutex<HashMap<FileId, FileSlot>>>,
+}
+
+/// A project file read from disk, cached along with the fingerprint of its content.
+struct FileSlot {
+    /// Modification time and size of the file when it was last read.
+    fingerprint: Option<(SystemTime, u64)>,
+    /// Raw content of the file.
+    bytes: FileResult<Bytes>,
+    /// Parsed content of the file, if it was requested as a source.
+    source: Option<FileResult<Source>>,
+}
+
+impl FileSlot {
+    /// Creates an empty [`FileSlot`] that will be filled on the first refresh.
+    fn new() -> Self {
+        Self {
+            fingerprint: None,
+            bytes: Err(FileError::Other(None)),
+            source: None,
+        }
+    }
+
+    /// Reads the file at `path` again if its fingerprint changed since the last read.
+    fn refresh(
+        &mut self,
+        path: &Path,
+    ) {
+        let fingerprint = fs::metadata(path)
+            .and_then(|meta| Ok((meta.modified()?, meta.len())))
+            .ok();
+        if fingerprint.is_some() && fingerprint == self.fingerprint {
+            return;
+        }
+        self.fingerprint = fingerprint;
+        self.bytes = if path.is_dir() {
+            Err(FileError::IsDirectory)
+        } else {
+            fs::read(path)
+                .map(Bytes::new)
+                .map_err(|err| FileError::from_io(err, path))
+        };
+        self.source = match (self.source.take(), &self.bytes) {
+            //reparse incrementally when the file was already a valid source
+            (Some(Ok(mut source)), Ok(bytes)) => decode_utf8(bytes).ok().map(|text| {
+                source.replace(&text);
+                Ok(source)
+            }),
+            _ => None,
+        };
+    }
+
+    /// Returns the file content parsed as a Typst [`Source`].
+    fn source(
+        &mut self,
+        id: FileId,
+    ) -> FileResult<Source> {
+        self.source
+            .get_or_insert_with(|| {
+                let bytes = self.bytes.clone()?;
+                decode_utf8(&bytes).map(|text| Source::new(id, text))
+            })
+            .clone()
+    }
 }
 
 #[cfg(test)]
 pub mod tests {
     use super::*;
+    use crate::font::EDITOR_REG_BYTES;
+    use typst::layout::PagedDocument;
 
     pub fn init_world() -> TideWorld {
         let main_file_id = FileId::new(None, VirtualPath::new("main"));
-        let mut world = TideWorld::new(main_file_id, None);
+        let mut world = TideWorld::new(
+            PathBuf::from(env!("CARGO_MANIFEST_DIR")),
+            main_file_id,
+            None,
+            PackageStorage::new(None, None),
+        );
         let main = Source::new(main_file_id, String::from("= Hello World"));
         world.add_source(main_file_id, main);
 
@@ -421,6 +739,185 @@ pub mod tests {
         assert_eq!(world.main(), new_file_id);
     }
 
+    #[test]
+    fn test_package_import() {
+        let packages_path = std::env::temp_dir().join("tide-test-package-import");
+        let package_dir = packages_path.join("local").join("greet").join("0.1.0");
+        fs::create_dir_all(&package_dir).unwrap();
+        fs::write(
+            package_dir.join("typst.toml"),
+            "[package]\nname = \"greet\"\nversion = \"0.1.0\"\nentrypoint = \"lib.typ\"\n",
+        )
+        .unwrap();
+        fs::write(package_dir.join("lib.typ"), "#let greet = [Hello]").unwrap();
+
+        let main_file_id = FileId::new(None, VirtualPath::new("main.typ"));
+        let mut world = TideWorld::new(
+            packages_path.clone(),
+            main_file_id,
+            None,
+            PackageStorage::new(Some(packages_path.clone()), None),
+        );
+        world.add_source(
+            main_file_id,
+            Source::new(
+                main_file_id,
+                String::from("#import \"@local/greet:0.1.0\": greet\n#greet"),
+            ),
+        );
+        let document = typst::compile::<typst::layout::PagedDocument>(&world).output;
+        assert!(document.is_ok());
+
+        world.reload_source_from_content(
+            main_file_id,
+            &Content::with_text("#import \"@local/greet:0.2.0\": greet"),
+        );
+        let document = typst::compile::<typst::layout::PagedDocument>(&world).output;
+        assert!(document.is_err()); //this version is not installed
+        let _ = fs::remove_dir_all(packages_path);
+    }
+
+    #[test]
+    fn test_lazy_loading() {
+        let root = std::env::temp_dir().join("tide-test-lazy-loading");
+        fs::create_dir_all(&root).unwrap();
+        fs::write(root.join("main.typ"), "= Hello").unwrap();
+        fs::write(root.join("logo.svg"), "fake SVG").unwrap();
+
+        let main_file_id = FileId::new(None, VirtualPath::new("main.typ"));
+        let mut world = TideWorld::new(
+            root.clone(),
+            main_file_id,
+            None,
+            PackageStorage::new(None, None),
+        );
+        assert_eq!(world.source(main_file_id).unwrap().text(), "= Hello"); //unwrap() is ok because the file exists
+        let asset_file_id = FileId::new(None, VirtualPath::new("logo.svg"));
+        assert_eq!(world.file(asset_file_id).unwrap().len(), "fake SVG".len()); //unwrap() is ok because the file exists
+        assert!(world
+            .file(FileId::new(None, VirtualPath::new("missing.png")))
+            .is_err());
+
+        //changes on disk are picked up...
+        fs::write(root.join("main.typ"), "= Hello World").unwrap();
+        assert_eq!(world.source(main_file_id).unwrap().text(), "= Hello World");
+
+        //...unless an editor buffer overrides the file
+        world.reload_source_from_content(main_file_id, &Content::with_text("= Buffer"));
+        fs::write(root.join("main.typ"), "= Disk").unwrap();
+        assert!(world.source(main_file_id).unwrap().text().starts_with("= Buffer"));
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn test_invalidate() {
+        let root = std::env::temp_dir().join("tide-test-invalidate");
+        fs::create_dir_all(&root).unwrap();
+        fs::write(root.join("data.csv"), "a,b").unwrap();
+
+        let main_file_id = FileId::new(None, VirtualPath::new("main.typ"));
+        let mut world = TideWorld::new(
+            root.clone(),
+            main_file_id,
+            None,
+            PackageStorage::new(None, None),
+        );
+        let data_file_id = FileId::new(None, VirtualPath::new("data.csv"));
+        assert_eq!(world.file(data_file_id).unwrap().as_slice(), b"a,b"); //unwrap() is ok because the file exists
+
+        //same length, possibly same modification time: only invalidation guarantees a re-read
+        fs::write(root.join("data.csv"), "c,d").unwrap();
+        world.invalidate(data_file_id);
+        assert_eq!(world.file(data_file_id).unwrap().as_slice(), b"c,d");
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn test_configure() {
+        let root = std::env::temp_dir().join("tide-test-configure");
+        fs::create_dir_all(&root).unwrap();
+        fs::write(
+            root.join("main.typ"),
+            "#assert.eq(sys.inputs.at(\"variant\"), \"draft\")\n\
+             #context assert.eq(text.size, 14pt)\n\
+             #context assert.eq(text.lang, \"fr\")\n\
+             #assert.eq(datetime.today(), datetime(year: 2024, month: 5, day: 1))",
+        )
+        .unwrap();
+
+        let main_file_id = FileId::new(None, VirtualPath::new("main.typ"));
+        let mut world = TideWorld::new(
+            root.clone(),
+            main_file_id,
+            None,
+            PackageStorage::new(None, None),
+        );
+        assert!(typst::compile::<PagedDocument>(&world).output.is_err());
+
+        let mut config = ProjectConfig::default();
+        config.inputs.insert("variant".to_string(), "draft".to_string());
+        config.style.size = Some(14.0);
+        config.style.lang = Some("fr".to_string());
+        config.build.date = Some("2024-05-01".to_string());
+        world.configure(&config);
+        assert!(typst::compile::<PagedDocument>(&world).output.is_ok());
+        assert!(world.config_warnings().is_empty());
+
+        config.style.lang = Some("french".to_string());
+        world.configure(&config);
+        assert_eq!(world.config_warnings().len(), 1);
+        assert_eq!(world.location(world.config_warnings()[0].span), None);
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn test_for_html() {
+        let main_file_id = FileId::new(None, VirtualPath::new("main.typ"));
+        let world = TideWorld::new(
+            std::env::temp_dir(),
+            main_file_id,
+            None,
+            PackageStorage::new(None, None),
+        );
+        assert!(world.library().global.scope().get("html").is_none());
+        assert!(world.for_html().library().global.scope().get("html").is_some());
+    }
+
+    #[test]
+    fn test_reload_fonts() {
+        let root = std::env::temp_dir().join("tide-test-reload-fonts");
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).unwrap();
+
+        let main_file_id = FileId::new(None, VirtualPath::new("main.typ"));
+        let mut world = TideWorld::new(
+            root.clone(),
+            main_file_id,
+            None,
+            PackageStorage::new(None, None),
+        );
+        assert!(!world.book().contains_family("md io"));
+
+        fs::create_dir_all(root.join(PROJECT_FONTS_DIR)).unwrap();
+        fs::write(root.join(PROJECT_FONTS_DIR).join("MDIO-Regular.ttf"), EDITOR_REG_BYTES)
+            .unwrap();
+        world.reload_fonts();
+        assert!(world.book().contains_family("md io"));
+
+        //the fonts of a project are not shared with the other worlds
+        let other_root = root.join("other");
+        fs::create_dir_all(&other_root).unwrap();
+        let other = TideWorld::new(
+            other_root,
+            main_file_id,
+            None,
+            PackageStorage::new(None, None),
+        );
+        assert!(!other.book().contains_family("md io"));
+        assert_eq!(other.fonts.len() + 1, world.fonts.len());
+        let _ = fs::remove_dir_all(root);
+    }
+
     #[test]
     fn test_reload_source() {
         let mut world = init_world();
@@ -432,9 +929,6 @@ pub mod tests {
             &Content::with_text("= Text modified"),
         );
         assert!(world.source(world.main()).is_ok());
-        assert_eq!(
-            world.source(world.main()).unwrap().text(),
-            "= Text modified\n"
-        ); //unwrap() is ok because of the test above
+        assert_eq!(world.source(world.main()).unwrap().text(), "= Text modified"); //unwrap() is ok because of the test above
     }
 }
@@ -0,0 +1,276 @@
+use fontdb::Database;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use typst::foundations::Bytes;
+use typst::text::{Font, FontBook, FontInfo, FontVariant};
+
+/// Name of the optional directory, at the root of a project, containing project fonts.
+pub const PROJECT_FONTS_DIR: &str = "fonts";
+
+const LIBERTINUS_SERIF_REGULAR: &[u8] =
+    include_bytes!("../../assets/fonts/libertinus/LibertinusSerif-Regular.ttf");
+const LIBERTINUS_SERIF_BOLD: &[u8] =
+    include_bytes!("../../assets/fonts/libertinus/LibertinusSerif-Bold.ttf");
+const LIBERTINUS_SERIF_ITALIC: &[u8] =
+    include_bytes!("../../assets/fonts/libertinus/LibertinusSerif-Italic.ttf");
+const LIBERTINUS_SERIF_BOLD_ITALIC: &[u8] =
+    include_bytes!("../../assets/fonts/libertinus/LibertinusSerif-BoldItalic.ttf");
+const LIBERTINUS_SERIF_SEMIBOLD: &[u8] =
+    include_bytes!("../../assets/fonts/libertinus/LibertinusSerif-Semibold.ttf");
+const LIBERTINUS_SERIF_MATH: &[u8] =
+    include_bytes!("../../assets/fonts/libertinus/LibertinusMath-Regular.ttf");
+const LIBERTINUS_SERIF_INITIALS: &[u8] =
+    include_bytes!("../../assets/fonts/libertinus/LibertinusSerifInitials-Regular.ttf");
+const NEW_CMM_MATH_REGULAR: &[u8] =
+    include_bytes!("../../assets/fonts/NewCMMath-Regular.otf");
+
+/// Fonts embedded in the Tide binary, always available.
+const EMBEDDED_FONTS: [&[u8]; 8] = [
+    LIBERTINUS_SERIF_REGULAR,
+    LIBERTINUS_SERIF_BOLD,
+    LIBERTINUS_SERIF_ITALIC,
+    LIBERTINUS_SERIF_BOLD_ITALIC,
+    LIBERTINUS_SERIF_SEMIBOLD,
+    LIBERTINUS_SERIF_MATH,
+    LIBERTINUS_SERIF_INITIALS,
+    NEW_CMM_MATH_REGULAR,
+];
+
+/// Where a font has been found.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FontSource {
+    /// Embedded in the Tide binary.
+    Embedded,
+    /// Found in the `fonts` directory of the Tide configuration.
+    User,
+    /// Installed on the system.
+    System,
+    /// Found in the `fonts` directory of the current project.
+    Project,
+}
+
+impl FontSource {
+    /// Returns a string representation of the source (e.g., "embedded", "system").
+    pub fn text(&self) -> &'static str {
+        match self {
+            Self::Embedded => "embedded",
+            Self::User => "user",
+            Self::System => "system",
+            Self::Project => "project",
+        }
+    }
+}
+
+/// A font family and its faces, as listed by the font manager.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FontFamily {
+    /// Name of the family, as expected by `#text(font: ...)`.
+    pub name: String,
+    /// Faces of the family, sorted by weight, style and stretch.
+    pub faces: Vec<FontFace>,
+}
+
+/// Description of a single face of a [`FontFamily`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FontFace {
+    /// Weight, style and stretch of the face.
+    pub variant: FontVariant,
+    /// Where the face has been found.
+    pub source: FontSource,
+    /// Number of Unicode code points covered by the face.
+    pub coverage: usize,
+}
+
+/// A font face known to the [`FontBook`], whose data is loaded on first use.
+#[derive(Clone, Debug)]
+pub struct FontSlot {
+    /// Metadata of the face, read when the font is discovered.
+    info: FontInfo,
+    /// Where the font has been found.
+    source: FontSource,
+    /// Path of the font file, `None` for embedded fonts.
+    path: Option<PathBuf>,
+    /// Index of the face in the font file.
+    index: u32,
+    /// The loaded font, `None` inside if the file can't be read or parsed anymore.
+    font: OnceLock<Option<Font>>,
+}
+
+impl FontSlot {
+    /// Returns where the font has been found.
+    pub fn source(&self) -> FontSource {
+        self.source
+    }
+
+    /// Returns the font, reading it from disk the first time it is requested.
+    pub fn get(&self) -> Option<Font> {
+        self.font
+            .get_or_init(|| {
+                let data = fs::read(self.path.as_ref()?).ok()?;
+                Font::new(Bytes::new(data), self.index)
+            })
+            .clone()
+    }
+}
+
+/// Discovers fonts without loading their data.
+///
+/// Only the metadata needed by the [`FontBook`] is read: font files are memory-mapped
+/// while they are scanned and read again on demand by [`FontSlot::get`].
+pub struct FontSearcher {
+    /// Fonts found so far, in the order of the future [`FontBook`].
+    slots: Vec<FontSlot>,
+}
+
+impl FontSearcher {
+    /// Creates a new [`FontSearcher`] knowing only the embedded fonts.
+    pub fn new() -> Self {
+        let slots = EMBEDDED_FONTS
+            .into_iter()
+            .flat_map(|data| Font::iter(Bytes::new(data))) //if the font data contain several faces
+            .map(|font| FontSlot {
+                info: font.info().clone(),
+                source: FontSource::Embedded,
+                path: None,
+                index: font.index(),
+                font: OnceLock::from(Some(font)),
+            })
+            .collect();
+        Self { slots }
+    }
+
+    /// Creates a new [`FontSearcher`] from fonts already discovered.
+    pub fn from_slots(slots: Vec<FontSlot>) -> Self {
+        Self { slots }
+    }
+
+    /// Searches the fonts installed in the standard system font directories.
+    pub fn search_system(&mut self) {
+        let mut database = Database::new();
+        database.load_system_fonts();
+        self.add(&database, FontSource::System);
+    }
+
+    /// Searches the fonts inside `dir` and its subdirectories.
+    pub fn search_dir(
+        &mut self,
+        dir: &Path,
+        source: FontSource,
+    ) {
+        let mut database = Database::new();
+        database.load_fonts_dir(dir);
+        self.add(&database, source);
+    }
+
+    /// Adds every file-backed face of `database` to the discovered fonts.
+    fn add(
+        &mut self,
+        database: &Database,
+        source: FontSource,
+    ) {
+        for face in database.faces() {
+            let path = match &face.source {
+                fontdb::Source::File(path) | fontdb::Source::SharedFile(path, _) => path,
+                fontdb::Source::Binary(_) => continue,
+            };
+            let info = database.with_face_data(face.id, FontInfo::new).flatten();
+            if let Some(info) = info {
+                self.slots.push(FontSlot {
+                    info,
+                    source,
+                    path: Some(path.clone()),
+                    index: face.index,
+                    font: OnceLock::new(),
+                });
+            }
+        }
+    }
+
+    /// Returns the discovered fonts and the [`FontBook`] describing them.
+    pub fn finish(self) -> (FontBook, Vec<FontSlot>) {
+        let book = FontBook::from_infos(self.slots.iter().map(|slot| slot.info.clone()));
+        (book, self.slots)
+    }
+}
+
+/// Groups the faces of the given fonts by family, sorted by family name.
+pub fn families(slots: &[FontSlot]) -> Vec<FontFamily> {
+    let mut families: BTreeMap<String, FontFamily> = BTreeMap::new();
+    for slot in slots {
+        let family = families
+            .entry(slot.info.family.to_lowercase())
+            .or_insert_with(|| FontFamily {
+                name: slot.info.family.clone(),
+                faces: vec![],
+            });
+        family.faces.push(FontFace {
+            variant: slot.info.variant,
+            source: slot.source,
+            coverage: slot.info.coverage.iter().count(),
+        });
+    }
+    families
+        .into_values()
+        .map(|mut family| {
+            family.faces.sort_by_key(|face| {
+                (
+                    face.variant.weight,
+                    face.variant.style,
+                    face.variant.stretch,
+                )
+            });
+            family
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_search_dir() {
+        let dir = std::env::temp_dir().join("tide-test-search-fonts");
+        let nested = dir.join("nested").join("deeper");
+        fs::create_dir_all(&nested).unwrap();
+        fs::write(nested.join("NewCMMath-Regular.otf"), NEW_CMM_MATH_REGULAR).unwrap();
+        fs::write(dir.join("not-a-font.otf"), "nothing here").unwrap();
+
+        let mut searcher = FontSearcher::from_slots(vec![]);
+        searcher.search_dir(&dir, FontSource::Project);
+        let (book, slots) = searcher.finish();
+        assert_eq!(slots.len(), 1);
+        assert_eq!(slots[0].source(), FontSource::Project);
+        assert!(book.contains_family("new computer modern math"));
+
+        //the font data is only read now
+        assert!(slots[0].get().is_some());
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_families() {
+        let (_, slots) = FontSearcher::new().finish();
+        let families = families(&slots);
+        let names: Vec<_> = families.iter().map(|family| family.name.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "Libertinus Math",
+                "Libertinus Serif",
+                "Libertinus Serif Initials",
+                "New Computer Modern Math"
+            ]
+        );
+
+        let serif = &families[1];
+        assert_eq!(serif.faces.len(), 5);
+        assert!(serif
+            .faces
+            .iter()
+            .all(|face| face.source == FontSource::Embedded && face.coverage > 0));
+        assert!(serif.faces.is_sorted_by_key(|face| face.variant.weight));
+    }
+}
@@ -0,0 +1,320 @@
+use dirs_next::{cache_dir, data_dir};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use typst::diag::{FileError, FileResult, PackageError, PackageResult};
+use typst::ecow::eco_format;
+use typst::foundations::Bytes;
+use typst::syntax::package::{PackageManifest, PackageSpec};
+use typst::syntax::{FileId, Source};
+
+/// Name of the manifest file found at the root of every Typst package.
+const MANIFEST_FILE_NAME: &str = "typst.toml";
+/// Namespace of the packages published on Typst Universe.
+const PREVIEW_NAMESPACE: &str = "preview";
+
+/// Downloads a package into a local directory so that it can be resolved from disk.
+///
+/// Resolution never depends on a downloader: packages already on disk are always
+/// found offline, the downloader is only asked for `@preview` packages that are missing.
+pub trait PackageDownloader: Send + Sync {
+    /// Downloads and unpacks the package described by `spec` into `destination`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`PackageError`] if the package can't be retrieved or unpacked.
+    fn download(
+        &self,
+        spec: &PackageSpec,
+        destination: &Path,
+    ) -> PackageResult<()>;
+}
+
+/// Resolves `@preview` and `@local` package imports from on-disk package directories.
+///
+/// Packages are searched in the user-configured package path first, then in the standard
+/// Typst data directory and, for `@preview` packages, in the standard Typst cache directory.
+/// Each directory follows the `<namespace>/<name>/<version>` layout used by Typst.
+///
+/// Resolved packages and their files are cached: a package never changes once installed.
+/// Failures are not cached, so that a package installed later, or whose download failed
+/// temporarily, is found by the next compilation. The caches are shared between clones
+/// so that background compilations benefit from them.
+#[derive(Clone)]
+pub struct PackageStorage {
+    /// Additional user-defined directory where packages are searched first.
+    package_path: Option<PathBuf>,
+    /// Standard Typst data directory, where `@local` packages are usually installed.
+    data_path: Option<PathBuf>,
+    /// Standard Typst cache directory, where `@preview` packages are downloaded.
+    cache_path: Option<PathBuf>,
+    /// Optional downloader used when a `@preview` package is not found on disk.
+    downloader: Option<Arc<dyn PackageDownloader>>,
+    /// Root directories of the packages resolved so far.
+    resolved: Arc<Mutex<HashMap<PackageSpec, PathBuf>>>,
+    /// Package files read so far.
+    files: Arc<Mutex<HashMap<FileId, Bytes>>>,
+    /// Package sources parsed so far.
+    sources: Arc<Mutex<HashMap<FileId, Source>>>,
+}
+
+impl PackageStorage {
+    /// Creates a new [`PackageStorage`] searching the standard Typst directories
+    /// and the optional `package_path`.
+    pub fn new(
+        package_path: Option<PathBuf>,
+        downloader: Option<Arc<dyn PackageDownloader>>,
+    ) -> Self {
+        Self {
+            package_path,
+            data_path: data_dir().map(|dir| dir.join("typst").join("packages")),
+            cache_path: cache_dir().map(|dir| dir.join("typst").join("packages")),
+            downloader,
+            resolved: Default::default(),
+            files: Default::default(),
+            sources: Default::default(),
+        }
+    }
+
+    /// Returns the directories searched for the given namespace, ordered by priority.
+    fn search_paths(
+        &self,
+        namespace: &str,
+    ) -> Vec<&PathBuf> {
+        let mut paths = vec![];
+        paths.extend(self.package_path.as_ref());
+        paths.extend(self.data_path.as_ref());
+        if namespace == PREVIEW_NAMESPACE {
+            paths.extend(self.cache_path.as_ref());
+        }
+        paths
+    }
+
+    /// Returns the root directory of the package described by `spec`.
+    ///
+    /// The package is downloaded first if it is missing and a downloader is available.
+    /// The cache is not locked meanwhile, so a slow download doesn't block the lookups
+    /// of other packages.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`PackageError`] if the package can't be found or if its manifest is invalid.
+    pub fn package_root(
+        &self,
+        spec: &PackageSpec,
+    ) -> PackageResult<PathBuf> {
+        if let Some(root) = self.resolved.lock().unwrap().get(spec) {
+            return Ok(root.clone());
+        }
+        let root = self.resolve(spec)?;
+        self.resolved
+            .lock()
+            .unwrap()
+            .insert(spec.clone(), root.clone());
+        Ok(root)
+    }
+
+    /// Looks for the package on disk, downloads it if necessary, and checks its manifest.
+    fn resolve(
+        &self,
+        spec: &PackageSpec,
+    ) -> PackageResult<PathBuf> {
+        let subdir = PathBuf::from(spec.namespace.as_str())
+            .join(spec.name.as_str())
+            .join(spec.version.to_string());
+
+        let found = self
+            .search_paths(&spec.namespace)
+            .into_iter()
+            .map(|path| path.join(&subdir))
+            .find(|dir| dir.is_dir());
+
+        let root = match (found, &self.downloader, &self.cache_path) {
+            (Some(root), _, _) => root,
+            (None, Some(downloader), Some(cache_path))
+                if spec.namespace == PREVIEW_NAMESPACE =>
+            {
+                let destination = cache_path.join(&subdir);
+                downloader.download(spec, &destination)?;
+                destination
+            }
+            _ => return Err(PackageError::NotFound(spec.clone())),
+        };
+
+        read_manifest(&root)?
+            .validate(spec)
+            .map_err(|err| PackageError::Other(Some(err)))?;
+
+        Ok(root)
+    }
+
+    /// Reads (or retrieves from cache) the raw content of a package file.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`FileError`] if the file doesn't belong to a package, if the package can't
+    /// be resolved, or if the file can't be read.
+    pub fn file(
+        &self,
+        id: FileId,
+    ) -> FileResult<Bytes> {
+        if let Some(bytes) = self.files.lock().unwrap().get(&id) {
+            return Ok(bytes.clone());
+        }
+        let bytes = self.read(id)?;
+        self.files.lock().unwrap().insert(id, bytes.clone());
+        Ok(bytes)
+    }
+
+    /// Reads (or retrieves from cache) a package file as a Typst [`Source`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`FileError`] if the file can't be read or is not valid UTF-8.
+    pub fn source(
+        &self,
+        id: FileId,
+    ) -> FileResult<Source> {
+        if let Some(source) = self.sources.lock().unwrap().get(&id) {
+            return Ok(source.clone());
+        }
+        let source = Source::new(id, decode_utf8(&self.file(id)?)?);
+        self.sources.lock().unwrap().insert(id, source.clone());
+        Ok(source)
+    }
+
+    /// Reads a package file from disk.
+    fn read(
+        &self,
+        id: FileId,
+    ) -> FileResult<Bytes> {
+        let Some(spec) = id.package() else {
+            return Err(FileError::NotFound(id.vpath().as_rootless_path().into()));
+        };
+        let root = self.package_root(spec).map_err(FileError::Package)?;
+        let path = id.vpath().resolve(&root).ok_or(FileError::AccessDenied)?;
+        if path.is_dir() {
+            return Err(FileError::IsDirectory);
+        }
+        fs::read(&path)
+            .map(Bytes::new)
+            .map_err(|err| FileError::from_io(err, &path))
+    }
+}
+
+/// Reads and parses the `typst.toml` manifest at the root of a package.
+///
+/// # Errors
+///
+/// Returns a [`PackageError`] if the manifest is missing or malformed.
+fn read_manifest(root: &Path) -> PackageResult<PackageManifest> {
+    let path = root.join(MANIFEST_FILE_NAME);
+    let content = fs::read_to_string(&path).map_err(|err| {
+        PackageError::Other(Some(eco_format!("can't read {}: {err}", path.display())))
+    })?;
+    toml::from_str(&content).map_err(|err| {
+        PackageError::Other(Some(eco_format!(
+            "package manifest is malformed: {}",
+            err.message()
+        )))
+    })
+}
+
+/// Decodes UTF-8 bytes into a string, ignoring a potential byte order mark.
+///
+/// # Errors
+///
+/// Returns a [`FileError::InvalidUtf8`] if the bytes are not valid UTF-8.
+pub fn decode_utf8(bytes: &[u8]) -> FileResult<String> {
+    let bytes = bytes.strip_prefix(b"\xef\xbb\xbf").unwrap_or(bytes);
+    String::from_utf8(bytes.to_vec()).map_err(|_| FileError::InvalidUtf8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+    use typst::syntax::VirtualPath;
+
+    const MANIFEST: &str = r#"
+[package]
+name = "hello"
+version = "0.1.0"
+entrypoint = "lib.typ"
+"#;
+
+    /// Creates a package directory `<root>/<namespace>/hello/0.1.0` with a manifest and a source.
+    fn create_package(
+        root: &Path,
+        namespace: &str,
+    ) -> PathBuf {
+        let dir = root.join(namespace).join("hello").join("0.1.0");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join(MANIFEST_FILE_NAME), MANIFEST).unwrap();
+        fs::write(dir.join("lib.typ"), "#let hello = [Hello]").unwrap();
+        dir
+    }
+
+    fn test_storage(root: &Path) -> PackageStorage {
+        PackageStorage {
+            package_path: Some(root.to_path_buf()),
+            data_path: None,
+            cache_path: None,
+            downloader: None,
+            resolved: Default::default(),
+            files: Default::default(),
+            sources: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_resolve_package() {
+        let root = std::env::temp_dir().join("tide-test-resolve-package");
+        let package_dir = create_package(&root, "local");
+        let storage = test_storage(&root);
+
+        let spec = PackageSpec::from_str("@local/hello:0.1.0").unwrap();
+        assert_eq!(storage.package_root(&spec), Ok(package_dir));
+
+        let id = FileId::new(Some(spec), VirtualPath::new("lib.typ"));
+        assert_eq!(storage.source(id).unwrap().text(), "#let hello = [Hello]"); //unwrap() is ok because the package exists
+
+        let missing = PackageSpec::from_str("@local/hello:0.2.0").unwrap();
+        assert!(storage.package_root(&missing).is_err());
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn test_package_installed_later() {
+        let root = std::env::temp_dir().join("tide-test-package-installed-later");
+        let _ = fs::remove_dir_all(&root);
+        let storage = test_storage(&root);
+
+        let spec = PackageSpec::from_str("@local/hello:0.1.0").unwrap();
+        let id = FileId::new(Some(spec.clone()), VirtualPath::new("lib.typ"));
+        assert!(storage.package_root(&spec).is_err());
+        assert!(storage.source(id).is_err());
+
+        let package_dir = create_package(&root, "local");
+        assert_eq!(storage.package_root(&spec), Ok(package_dir));
+        assert!(storage.source(id).is_ok());
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn test_invalid_manifest() {
+        let root = std::env::temp_dir().join("tide-test-invalid-manifest");
+        let package_dir = create_package(&root, "preview");
+        fs::write(
+            package_dir.join(MANIFEST_FILE_NAME),
+            MANIFEST.replace("hello", "other"),
+        )
+        .unwrap();
+        let storage = test_storage(&root);
+
+        let spec = PackageSpec::from_str("@preview/hello:0.1.0").unwrap();
+        assert!(storage.package_root(&spec).is_err());
+        let _ = fs::remove_dir_all(root);
+    }
+}